@@ -4,8 +4,13 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use miaw_lp_staking::msg::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, StakerInfoResponse,
-    StateResponse, StakersInfoResponse
+    AllNftInfoResponse, AllowlistResponse, ApprovalsResponse, ConfigResponse, ContractInfoResponse,
+    Cw20HookMsg, Cw721ReceiverExecuteMsg, EmissionCurveResponse, ExecuteMsg, HooksResponse,
+    InstantiateMsg, NftApprovalResponse, NftApprovalsResponse, NftInfoResponse, NumTokensResponse,
+    OperatorsResponse, OwnerOfResponse, QueryMsg, RefereesResponse, ReferralEarningsResponse,
+    RewardDustResponse, RewarderExecuteMsg, RewarderPendingRewardsResponse, RewarderQueryMsg,
+    SimulationResponse, StakeChangedExecuteMsg, StakerInfoResponse, StakersInfoResponse,
+    StateResponse, StatusResponse, TokensResponse, UndistributedRewardResponse, VestingResponse,
 };
 
 fn main() {
@@ -20,6 +25,31 @@ fn main() {
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(ConfigResponse), &out_dir);
     export_schema(&schema_for!(StateResponse), &out_dir);
+    export_schema(&schema_for!(StatusResponse), &out_dir);
     export_schema(&schema_for!(StakerInfoResponse), &out_dir);
     export_schema(&schema_for!(StakersInfoResponse), &out_dir);
+    export_schema(&schema_for!(SimulationResponse), &out_dir);
+    export_schema(&schema_for!(RewardDustResponse), &out_dir);
+    export_schema(&schema_for!(UndistributedRewardResponse), &out_dir);
+    export_schema(&schema_for!(VestingResponse), &out_dir);
+    export_schema(&schema_for!(RefereesResponse), &out_dir);
+    export_schema(&schema_for!(ReferralEarningsResponse), &out_dir);
+    export_schema(&schema_for!(AllowlistResponse), &out_dir);
+    export_schema(&schema_for!(ApprovalsResponse), &out_dir);
+    export_schema(&schema_for!(HooksResponse), &out_dir);
+    export_schema(&schema_for!(EmissionCurveResponse), &out_dir);
+    export_schema(&schema_for!(StakeChangedExecuteMsg), &out_dir);
+    export_schema(&schema_for!(RewarderExecuteMsg), &out_dir);
+    export_schema(&schema_for!(RewarderQueryMsg), &out_dir);
+    export_schema(&schema_for!(RewarderPendingRewardsResponse), &out_dir);
+    export_schema(&schema_for!(OwnerOfResponse), &out_dir);
+    export_schema(&schema_for!(NftInfoResponse), &out_dir);
+    export_schema(&schema_for!(AllNftInfoResponse), &out_dir);
+    export_schema(&schema_for!(NftApprovalResponse), &out_dir);
+    export_schema(&schema_for!(NftApprovalsResponse), &out_dir);
+    export_schema(&schema_for!(OperatorsResponse), &out_dir);
+    export_schema(&schema_for!(TokensResponse), &out_dir);
+    export_schema(&schema_for!(NumTokensResponse), &out_dir);
+    export_schema(&schema_for!(ContractInfoResponse), &out_dir);
+    export_schema(&schema_for!(Cw721ReceiverExecuteMsg), &out_dir);
 }
@@ -1,27 +1,76 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response,
+    from_binary, to_binary, Addr, Binary, CanonicalAddr, ContractResult, Deps, DepsMut, Env,
+    MessageInfo, Order, Reply, Response, StdError, StdResult,
 };
 use cw20::Cw20ReceiveMsg;
 
 use crate::error::ContractError;
-use crate::handle::{bond, bond_hook, claim_rewards, unbond};
-use crate::msg::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
-use crate::query::{query_config, query_staker_info, query_stakers_info, query_state};
-use crate::state::{Config, State, CONFIG, STATE};
+use crate::handle::{
+    add_hook, approve_all, approve_nft, approve_operator, bond, bond_hook, bond_position,
+    claim_position_rewards, claim_rewards, deposit_referral_reserve, deposit_reward,
+    distribute_to_stakers, kick, notify_reward_amount, receipt_transfer_hook,
+    recover_undistributed, register_receipt_token, remove_hook, revoke_all, revoke_nft,
+    revoke_operator, rewarder_failed, send_nft, set_keeper, set_rewarder,
+    stake_changed_hook_failed, sweep_tokens, transfer_nft, transfer_position, unbond,
+    unbond_position, update_allowlist, update_config, validate_distribution_schedule,
+    withdraw_vested, withdraw_vested_early, REWARDER_REPLY_ID, STAKE_CHANGED_HOOK_REPLY_ID,
+};
+use crate::msg::{
+    BoostSource, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, UndistributedPolicy,
+};
+use crate::query::{
+    query_all_nft_info, query_all_operators, query_all_tokens, query_allowlist, query_config,
+    query_contract_info, query_emission_curve, query_hooks, query_nft_approval,
+    query_nft_approvals, query_nft_info, query_num_tokens, query_operators, query_owner_of,
+    query_referees, query_referral_earnings, query_reward_dust, query_simulate_bond,
+    query_simulate_claim, query_simulate_unbond, query_staker_info, query_stakers_by_address,
+    query_stakers_info, query_state, query_status, query_tokens, query_undistributed_reward,
+    query_vesting,
+};
+use crate::state::{
+    positions, staker_infos, Config, LegacyConfig, StakerInfo, State, CONFIG, DEFAULT_LIMIT,
+    LEGACY_CONFIG, MAX_LIMIT, STATE,
+};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    validate_distribution_schedule(&msg.distribution_schedule)?;
+
     let config = Config {
+        owner: deps.api.addr_canonicalize(info.sender.as_str())?,
         miaw_token: deps.api.addr_canonicalize(&msg.miaw_token)?,
         miaw_lp_token: deps.api.addr_canonicalize(&msg.miaw_lp_token)?,
         distribution_schedule: msg.distribution_schedule,
+        max_limit: MAX_LIMIT,
+        default_limit: DEFAULT_LIMIT,
+        undistributed_policy: UndistributedPolicy::default(),
+        vesting_duration: 0,
+        early_withdraw_penalty: Decimal256::zero(),
+        boost_contract: None,
+        boost_source: BoostSource::default(),
+        referral_rate: Decimal256::zero(),
+        reward_fee_rate: Decimal256::zero(),
+        fee_collector: None,
+        allowlist_enabled: false,
+        max_bond_per_staker: Uint256::zero(),
+        max_total_bond: Uint256::zero(),
+        deposit_fee_rate: Decimal256::zero(),
+        treasury: None,
+        start_time: None,
+        end_time: None,
+        paused: false,
+        receipt_token: None,
+        rewarder: None,
+        reward_deposit_duration: 0,
+        keeper: None,
     };
     CONFIG.save(deps.storage, &config)?;
 
@@ -45,9 +94,78 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
-        ExecuteMsg::Unbond { amount } => unbond(deps, env, info, amount),
-        ExecuteMsg::ClaimRewards {} => claim_rewards(deps, env, info),
+        ExecuteMsg::Unbond {
+            amount,
+            staker,
+            token_id,
+        } => match token_id {
+            Some(token_id) => unbond_position(deps, env, info, token_id, amount),
+            None => unbond(deps, env, info, staker, amount),
+        },
+        ExecuteMsg::ClaimRewards { staker, token_id } => match token_id {
+            Some(token_id) => claim_position_rewards(deps, env, info, token_id),
+            None => claim_rewards(deps, env, info, staker),
+        },
+        ExecuteMsg::DistributeToStakers { start_after, limit } => {
+            distribute_to_stakers(deps, env, info, start_after, limit)
+        }
         ExecuteMsg::BondHook {} => bond_hook(deps, env, info),
+        ExecuteMsg::UpdateConfig(msg) => update_config(deps, env, info, msg),
+        ExecuteMsg::ApproveOperator { operator, expires } => {
+            approve_operator(deps, env, info, operator, expires)
+        }
+        ExecuteMsg::RevokeOperator { operator } => revoke_operator(deps, info, operator),
+        ExecuteMsg::SetRewarder { rewarder } => set_rewarder(deps, info, rewarder),
+        ExecuteMsg::SetKeeper { keeper } => set_keeper(deps, info, keeper),
+        ExecuteMsg::AddHook {
+            addr,
+            revert_on_failure,
+            gas_limit,
+        } => add_hook(deps, info, addr, revert_on_failure, gas_limit),
+        ExecuteMsg::RemoveHook { addr } => remove_hook(deps, info, addr),
+        ExecuteMsg::UpdateAllowlist { add, remove } => update_allowlist(deps, info, add, remove),
+        ExecuteMsg::RecoverUndistributed { recipient } => {
+            recover_undistributed(deps, env, info, recipient)
+        }
+        ExecuteMsg::SweepTokens {
+            asset,
+            recipient,
+            amount,
+        } => sweep_tokens(deps, env, info, asset, recipient, amount),
+        ExecuteMsg::Kick { staker } => kick(deps, env, staker),
+        ExecuteMsg::TransferPosition {
+            recipient,
+            amount,
+            include_rewards,
+        } => transfer_position(deps, env, info, recipient, amount, include_rewards),
+        ExecuteMsg::WithdrawVested {} => withdraw_vested(deps, env, info),
+        ExecuteMsg::WithdrawVestedEarly {} => withdraw_vested_early(deps, env, info),
+        ExecuteMsg::NotifyRewardAmount { amount } => notify_reward_amount(deps, env, info, amount),
+        ExecuteMsg::RegisterReceiptToken { receipt_token } => {
+            register_receipt_token(deps, info, receipt_token)
+        }
+        ExecuteMsg::ReceiptTransferHook { from, to, amount } => {
+            receipt_transfer_hook(deps, env, info, from, to, amount)
+        }
+        ExecuteMsg::TransferNft {
+            recipient,
+            token_id,
+        } => transfer_nft(deps, env, info, recipient, token_id),
+        ExecuteMsg::SendNft {
+            contract,
+            token_id,
+            msg,
+        } => send_nft(deps, env, info, contract, token_id, msg),
+        ExecuteMsg::Approve {
+            spender,
+            token_id,
+            expires,
+        } => approve_nft(deps, env, info, spender, token_id, expires),
+        ExecuteMsg::Revoke { spender, token_id } => revoke_nft(deps, env, info, spender, token_id),
+        ExecuteMsg::ApproveAll { operator, expires } => {
+            approve_all(deps, env, info, operator, expires)
+        }
+        ExecuteMsg::RevokeAll { operator } => revoke_all(deps, info, operator),
     }
 }
 
@@ -58,38 +176,264 @@ pub fn receive_cw20(
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
-    if config.miaw_lp_token != deps.api.addr_canonicalize(info.sender.as_str())? {
-        return Err(ContractError::Unauthorized {});
-    }
+    let token_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
     let cw20_sender: Addr = deps.api.addr_validate(&cw20_msg.sender)?;
 
     match from_binary(&cw20_msg.msg) {
-        Ok(Cw20HookMsg::Bond {}) => bond(deps, env, cw20_sender, cw20_msg.amount.into()),
+        Ok(Cw20HookMsg::Bond { referrer }) => {
+            if config.miaw_lp_token != token_raw {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            bond(deps, env, cw20_sender, cw20_msg.amount.into(), referrer)
+        }
+        Ok(Cw20HookMsg::BondPosition {}) => {
+            if config.miaw_lp_token != token_raw {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            bond_position(deps, env, cw20_sender, cw20_msg.amount.into())
+        }
+        Ok(Cw20HookMsg::DepositReward {}) => {
+            if config.miaw_token != token_raw {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            deposit_reward(deps, env, cw20_sender, cw20_msg.amount.into())
+        }
+        Ok(Cw20HookMsg::DepositReferralReserve {}) => {
+            if config.miaw_token != token_raw {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            deposit_referral_reserve(deps, cw20_sender, cw20_msg.amount.into())
+        }
         Err(_) => Err(ContractError::InvalidCw20Msg {}),
     }
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match (msg.id, msg.result) {
+        (STAKE_CHANGED_HOOK_REPLY_ID, ContractResult::Err(error)) => {
+            stake_changed_hook_failed(error)
+        }
+        (REWARDER_REPLY_ID, ContractResult::Err(error)) => rewarder_failed(error),
+        _ => Err(ContractError::Std(StdError::generic_err("Unknown reply"))),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::Config {} => Ok(to_binary(&query_config(deps)?)?),
         QueryMsg::State {} => Ok(to_binary(&query_state(deps)?)?),
+        QueryMsg::Status {} => Ok(to_binary(&query_status(deps, env)?)?),
+        QueryMsg::RewardDust {} => Ok(to_binary(&query_reward_dust(deps, env)?)?),
+        QueryMsg::UndistributedReward {} => Ok(to_binary(&query_undistributed_reward(deps, env)?)?),
         QueryMsg::StakerInfo { staker } => Ok(to_binary(&query_staker_info(deps, env, staker)?)?),
+        QueryMsg::StakersByAddress { addresses } => {
+            Ok(to_binary(&query_stakers_by_address(deps, env, addresses)?)?)
+        }
         QueryMsg::StakersInfo {
             start_after,
             limit,
             order_by,
+            sort_by,
+            min_bond,
         } => Ok(to_binary(&query_stakers_info(
             deps,
             env,
             start_after,
             limit,
             order_by,
+            sort_by,
+            min_bond,
+        )?)?),
+        QueryMsg::SimulateBond {
+            staker,
+            amount,
+            time,
+        } => Ok(to_binary(&query_simulate_bond(
+            deps, env, staker, amount, time,
+        )?)?),
+        QueryMsg::SimulateUnbond {
+            staker,
+            amount,
+            time,
+        } => Ok(to_binary(&query_simulate_unbond(
+            deps, env, staker, amount, time,
+        )?)?),
+        QueryMsg::SimulateClaim { staker, time } => {
+            Ok(to_binary(&query_simulate_claim(deps, env, staker, time)?)?)
+        }
+        QueryMsg::Vesting { staker } => Ok(to_binary(&query_vesting(deps, env, staker)?)?),
+        QueryMsg::Referees {
+            referrer,
+            start_after,
+            limit,
+        } => Ok(to_binary(&query_referees(
+            deps,
+            referrer,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::Allowlist { start_after, limit } => {
+            Ok(to_binary(&query_allowlist(deps, start_after, limit)?)?)
+        }
+        QueryMsg::Operators { staker } => Ok(to_binary(&query_operators(deps, env, staker)?)?),
+        QueryMsg::Hooks {} => Ok(to_binary(&query_hooks(deps)?)?),
+        QueryMsg::EmissionCurve { timestamps } => {
+            Ok(to_binary(&query_emission_curve(deps, timestamps)?)?)
+        }
+        QueryMsg::ReferralEarnings { referrer } => {
+            Ok(to_binary(&query_referral_earnings(deps, referrer)?)?)
+        }
+        QueryMsg::OwnerOf {
+            token_id,
+            include_expired,
+        } => Ok(to_binary(&query_owner_of(
+            deps,
+            env,
+            token_id,
+            include_expired,
+        )?)?),
+        QueryMsg::NftInfo { token_id } => Ok(to_binary(&query_nft_info(deps, env, token_id)?)?),
+        QueryMsg::AllNftInfo {
+            token_id,
+            include_expired,
+        } => Ok(to_binary(&query_all_nft_info(
+            deps,
+            env,
+            token_id,
+            include_expired,
+        )?)?),
+        QueryMsg::Tokens {
+            owner,
+            start_after,
+            limit,
+        } => Ok(to_binary(&query_tokens(deps, owner, start_after, limit)?)?),
+        QueryMsg::AllTokens { start_after, limit } => {
+            Ok(to_binary(&query_all_tokens(deps, start_after, limit)?)?)
+        }
+        QueryMsg::NumTokens {} => Ok(to_binary(&query_num_tokens(deps)?)?),
+        QueryMsg::ContractInfo {} => Ok(to_binary(&query_contract_info()?)?),
+        QueryMsg::Approval {
+            token_id,
+            spender,
+            include_expired,
+        } => Ok(to_binary(&query_nft_approval(
+            deps,
+            env,
+            token_id,
+            spender,
+            include_expired,
+        )?)?),
+        QueryMsg::Approvals {
+            token_id,
+            include_expired,
+        } => Ok(to_binary(&query_nft_approvals(
+            deps,
+            env,
+            token_id,
+            include_expired,
+        )?)?),
+        QueryMsg::AllOperators {
+            owner,
+            include_expired,
+            start_after,
+            limit,
+        } => Ok(to_binary(&query_all_operators(
+            deps,
+            env,
+            owner,
+            include_expired,
+            start_after,
+            limit,
         )?)?),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    // upgrade the config stored before the owner and page limits existed
+    if CONFIG.load(deps.storage).is_err() {
+        let legacy_config: LegacyConfig = LEGACY_CONFIG.load(deps.storage)?;
+        let owner: String = msg.owner.ok_or_else(|| {
+            StdError::generic_err("owner is required to migrate the legacy config")
+        })?;
+
+        CONFIG.save(
+            deps.storage,
+            &Config {
+                owner: deps.api.addr_canonicalize(&owner)?,
+                miaw_token: legacy_config.miaw_token,
+                miaw_lp_token: legacy_config.miaw_lp_token,
+                distribution_schedule: legacy_config.distribution_schedule,
+                max_limit: MAX_LIMIT,
+                default_limit: DEFAULT_LIMIT,
+                undistributed_policy: UndistributedPolicy::default(),
+                vesting_duration: 0,
+                early_withdraw_penalty: Decimal256::zero(),
+                boost_contract: None,
+                boost_source: BoostSource::default(),
+                referral_rate: Decimal256::zero(),
+                reward_fee_rate: Decimal256::zero(),
+                fee_collector: None,
+                allowlist_enabled: false,
+                max_bond_per_staker: Uint256::zero(),
+                max_total_bond: Uint256::zero(),
+                deposit_fee_rate: Decimal256::zero(),
+                treasury: None,
+                start_time: None,
+                end_time: None,
+                paused: false,
+                receipt_token: None,
+                rewarder: None,
+                reward_deposit_duration: 0,
+                keeper: None,
+            },
+        )?;
+    }
+
+    // build the secondary indexes of the stakers stored before they existed
+    // and weight the stakers stored before the boost existed by their bond amount
+    let stakers: Vec<(Vec<u8>, StakerInfo)> = staker_infos()
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(Vec<u8>, StakerInfo)>>>()?;
+    let mut state: State = STATE.load(deps.storage)?;
+    let mut total_weight: Uint256 = Uint256::zero();
+    let mut owed_amount: Uint256 = Uint256::zero();
+    for (k, staker_info) in stakers.iter() {
+        let mut new_staker_info: StakerInfo = staker_info.clone();
+        if new_staker_info.weight.is_zero() {
+            new_staker_info.weight = new_staker_info.bond_amount;
+        }
+        total_weight += new_staker_info.weight;
+        owed_amount += new_staker_info.pending_reward
+            + new_staker_info.weight * (state.global_reward_index - new_staker_info.reward_index);
+
+        staker_infos().replace(deps.storage, k, Some(&new_staker_info), None)?;
+    }
+
+    // the positions are weighted since they exist, but owe rewards as well
+    for item in positions().range(deps.storage, None, None, Order::Ascending) {
+        let (_, position) = item?;
+        total_weight += position.staker_info.weight;
+        owed_amount += position.staker_info.pending_reward
+            + position.staker_info.weight
+                * (state.global_reward_index - position.staker_info.reward_index);
+    }
+
+    // count the stakers stored before the count was tracked
+    state.staker_count = stakers.len() as u64;
+    state.total_weight = total_weight;
+
+    // account the rewards owed to the stakers before the reward counters were tracked
+    if state.cumulative_reward_distributed.is_zero() {
+        state.cumulative_reward_distributed = state.cumulative_reward_claimed + owed_amount;
+    }
+    STATE.save(deps.storage, &state)?;
+
     Ok(Response::default())
 }
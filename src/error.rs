@@ -17,4 +17,115 @@ pub enum ContractError {
 
     #[error("Invalid Cw20 msg")]
     InvalidCw20Msg {},
+
+    #[error("Invalid pagination cursor")]
+    InvalidCursor {},
+
+    #[error("Filtering by min bond requires sorting by bond amount")]
+    MinBondRequiresBondOrder {},
+
+    #[error("Default limit must be positive and not exceed max limit")]
+    InvalidPageLimits {},
+
+    #[error("Undistributed rewards are carried forward to stakers")]
+    UndistributedNotReclaimable {},
+
+    #[error("Nothing to recover")]
+    NothingToRecover {},
+
+    #[error("Cannot sweep more than the surplus balance")]
+    SweepAmountExceedsSurplus {},
+
+    #[error("Early withdraw penalty must not exceed 1")]
+    InvalidPenaltyRate {},
+
+    #[error("Nothing to withdraw")]
+    NothingToWithdraw {},
+
+    #[error("Early withdraw of vesting rewards is disabled")]
+    EarlyWithdrawDisabled {},
+
+    #[error("Boost of the staker has not decayed")]
+    BoostNotDecayed {},
+
+    #[error("Referral rate exceeds the maximum")]
+    InvalidReferralRate {},
+
+    #[error("Cannot refer yourself")]
+    SelfReferral {},
+
+    #[error("Referrer is referred by the staker")]
+    ReferralCycle {},
+
+    #[error("Reward fee rate exceeds the maximum")]
+    InvalidRewardFeeRate {},
+
+    #[error("Fee collector must be set to charge a reward fee")]
+    FeeCollectorNotSet {},
+
+    #[error("Deposit fee rate exceeds the maximum")]
+    InvalidDepositFeeRate {},
+
+    #[error("Treasury must be set to charge a deposit fee")]
+    TreasuryNotSet {},
+
+    #[error("Address is not on the allowlist")]
+    NotAllowlisted {},
+
+    #[error("Bond amount exceeds the cap per staker")]
+    StakerBondCapExceeded {},
+
+    #[error("Total bond amount exceeds the cap")]
+    TotalBondCapExceeded {},
+
+    #[error("Farm start time must be before end time")]
+    InvalidFarmWindow {},
+
+    #[error("Farm is not active")]
+    FarmNotActive {},
+
+    #[error("Cannot transfer more than bond amount")]
+    InvalidTransferAmount {},
+
+    #[error("Cannot transfer the position to yourself")]
+    InvalidTransferRecipient {},
+
+    #[error("Receipt token can only be registered once before any bond")]
+    ReceiptTokenRegistrationClosed {},
+
+    #[error("Positions move with the receipt token transfers")]
+    ReceiptTokenEnabled {},
+
+    #[error("Cannot approve yourself as operator")]
+    CannotApproveSelf {},
+
+    #[error("Approval expiration is already in the past")]
+    ApprovalExpired {},
+
+    #[error("Approval not found")]
+    ApprovalNotFound {},
+
+    #[error("Hook is already registered")]
+    HookAlreadyRegistered {},
+
+    #[error("Hook is not registered")]
+    HookNotRegistered {},
+
+    #[error("Cannot register more than {max} hooks")]
+    TooManyHooks { max: usize },
+
+    #[error("Reward balance does not cover the notified amount")]
+    InsufficientRewardBalance {},
+
+    #[error("Invalid distribution schedule")]
+    InvalidDistributionSchedule {},
+
+    #[error("Cannot sample more than {max} timestamps")]
+    TooManySamples { max: u32 },
+
+    #[error("Cannot query more than {max} addresses")]
+    TooManyAddresses { max: u32 },
+
+    #[error("Simulation time must not be in the past")]
+    InvalidSimulationTime {},
 }
@@ -0,0 +1,135 @@
+//! Event types and attribute keys emitted by the staking contract.
+//!
+//! Every state transition emits a typed event next to the legacy `action`
+//! attributes. The chain reports them as `wasm-<type>` events.
+
+use cosmwasm_bignumber::Decimal256;
+use cosmwasm_std::{Addr, Api, Event, StdResult};
+
+use crate::state::{Config, StakerInfo, State};
+
+/// Global reward index update, emitted whenever rewards are settled
+pub const EVENT_DISTRIBUTE: &str = "distribute";
+pub const EVENT_BOND: &str = "bond";
+pub const EVENT_UNBOND: &str = "unbond";
+pub const EVENT_CLAIM_REWARDS: &str = "claim_rewards";
+/// Position moved by `TransferPosition` or a receipt token transfer,
+/// emitted once for each side
+pub const EVENT_TRANSFER_POSITION_OUT: &str = "transfer_position_out";
+pub const EVENT_TRANSFER_POSITION_IN: &str = "transfer_position_in";
+pub const EVENT_KICK: &str = "kick";
+/// cw721 position transitions, `staker` is the owner of the position
+pub const EVENT_BOND_POSITION: &str = "bond_position";
+pub const EVENT_UNBOND_POSITION: &str = "unbond_position";
+pub const EVENT_CLAIM_POSITION_REWARDS: &str = "claim_position_rewards";
+pub const EVENT_TRANSFER_NFT: &str = "transfer_nft";
+/// cw721 approvals of the positions, separate from the staking operators
+pub const EVENT_APPROVE: &str = "approve";
+pub const EVENT_REVOKE: &str = "revoke";
+pub const EVENT_APPROVE_ALL: &str = "approve_all";
+pub const EVENT_REVOKE_ALL: &str = "revoke_all";
+pub const EVENT_WITHDRAW_VESTED: &str = "withdraw_vested";
+pub const EVENT_WITHDRAW_VESTED_EARLY: &str = "withdraw_vested_early";
+pub const EVENT_DEPOSIT_REWARD: &str = "deposit_reward";
+pub const EVENT_DEPOSIT_REFERRAL_RESERVE: &str = "deposit_referral_reserve";
+pub const EVENT_NOTIFY_REWARD_AMOUNT: &str = "notify_reward_amount";
+pub const EVENT_APPROVE_OPERATOR: &str = "approve_operator";
+pub const EVENT_REVOKE_OPERATOR: &str = "revoke_operator";
+pub const EVENT_RECOVER_UNDISTRIBUTED: &str = "recover_undistributed";
+pub const EVENT_SWEEP_TOKENS: &str = "sweep_tokens";
+/// Owner settings, `updated` lists the changed `UpdateConfig` groups
+pub const EVENT_UPDATE_CONFIG: &str = "update_config";
+pub const EVENT_UPDATE_ALLOWLIST: &str = "update_allowlist";
+pub const EVENT_REGISTER_RECEIPT_TOKEN: &str = "register_receipt_token";
+pub const EVENT_SET_REWARDER: &str = "set_rewarder";
+pub const EVENT_SET_KEEPER: &str = "set_keeper";
+pub const EVENT_ADD_HOOK: &str = "add_hook";
+pub const EVENT_REMOVE_HOOK: &str = "remove_hook";
+/// Failure of a submessage allowed to fail, reported by the reply entry point
+pub const EVENT_STAKE_CHANGED_HOOK_FAILED: &str = "stake_changed_hook_failed";
+pub const EVENT_REWARDER_FAILED: &str = "rewarder_failed";
+
+pub const ATTR_REWARD_ASSET: &str = "reward_asset";
+pub const ATTR_GLOBAL_REWARD_INDEX_BEFORE: &str = "global_reward_index_before";
+pub const ATTR_GLOBAL_REWARD_INDEX: &str = "global_reward_index";
+pub const ATTR_LAST_DISTRIBUTED: &str = "last_distributed";
+pub const ATTR_TOTAL_BOND_AMOUNT: &str = "total_bond_amount";
+pub const ATTR_TOTAL_WEIGHT: &str = "total_weight";
+
+pub const ATTR_STAKER: &str = "staker";
+pub const ATTR_SENDER: &str = "sender";
+pub const ATTR_COUNTERPARTY: &str = "counterparty";
+pub const ATTR_BOND_AMOUNT: &str = "bond_amount";
+pub const ATTR_REWARD_INDEX: &str = "reward_index";
+pub const ATTR_PENDING_REWARD: &str = "pending_reward";
+pub const ATTR_WEIGHT: &str = "weight";
+pub const ATTR_TOKEN_ID: &str = "token_id";
+pub const ATTR_BURNED: &str = "burned";
+
+pub const ATTR_AMOUNT: &str = "amount";
+pub const ATTR_REWARD_AMOUNT: &str = "reward_amount";
+pub const ATTR_DEPOSIT_FEE: &str = "deposit_fee";
+pub const ATTR_FEE_AMOUNT: &str = "fee_amount";
+pub const ATTR_PENALTY_AMOUNT: &str = "penalty_amount";
+pub const ATTR_REFERRER: &str = "referrer";
+pub const ATTR_REFERRAL_AMOUNT: &str = "referral_amount";
+pub const ATTR_REFERRAL_RESERVE: &str = "referral_reserve";
+pub const ATTR_VESTING_END_TIME: &str = "vesting_end_time";
+pub const ATTR_REWARD_STREAM_END_TIME: &str = "reward_stream_end_time";
+pub const ATTR_REWARD_RATE: &str = "reward_rate";
+
+pub const ATTR_OPERATOR: &str = "operator";
+pub const ATTR_SPENDER: &str = "spender";
+pub const ATTR_EXPIRES: &str = "expires";
+pub const ATTR_RECIPIENT: &str = "recipient";
+pub const ATTR_ASSET: &str = "asset";
+pub const ATTR_UPDATED: &str = "updated";
+pub const ATTR_ADDED: &str = "added";
+pub const ATTR_REMOVED: &str = "removed";
+pub const ATTR_RECEIPT_TOKEN: &str = "receipt_token";
+pub const ATTR_REWARDER: &str = "rewarder";
+pub const ATTR_KEEPER: &str = "keeper";
+pub const ATTR_HOOK: &str = "hook";
+pub const ATTR_REVERT_ON_FAILURE: &str = "revert_on_failure";
+pub const ATTR_GAS_LIMIT: &str = "gas_limit";
+pub const ATTR_ERROR: &str = "error";
+
+pub(crate) fn distribute_event(
+    api: &dyn Api,
+    config: &Config,
+    global_reward_index_before: Decimal256,
+    state: &State,
+) -> StdResult<Event> {
+    Ok(Event::new(EVENT_DISTRIBUTE)
+        .add_attribute(
+            ATTR_REWARD_ASSET,
+            api.addr_humanize(&config.miaw_token)?.to_string(),
+        )
+        .add_attribute(
+            ATTR_GLOBAL_REWARD_INDEX_BEFORE,
+            global_reward_index_before.to_string(),
+        )
+        .add_attribute(
+            ATTR_GLOBAL_REWARD_INDEX,
+            state.global_reward_index.to_string(),
+        )
+        .add_attribute(ATTR_LAST_DISTRIBUTED, state.last_distributed.to_string())
+        .add_attribute(ATTR_TOTAL_BOND_AMOUNT, state.total_bond_amount.to_string())
+        .add_attribute(ATTR_TOTAL_WEIGHT, state.total_weight.to_string()))
+}
+
+// the staker position after the transition
+pub(crate) fn staker_event(
+    ty: &str,
+    staker: &Addr,
+    staker_info: &StakerInfo,
+    state: &State,
+) -> Event {
+    Event::new(ty)
+        .add_attribute(ATTR_STAKER, staker.to_string())
+        .add_attribute(ATTR_BOND_AMOUNT, staker_info.bond_amount.to_string())
+        .add_attribute(ATTR_REWARD_INDEX, staker_info.reward_index.to_string())
+        .add_attribute(ATTR_PENDING_REWARD, staker_info.pending_reward.to_string())
+        .add_attribute(ATTR_WEIGHT, staker_info.weight.to_string())
+        .add_attribute(ATTR_TOTAL_BOND_AMOUNT, state.total_bond_amount.to_string())
+}
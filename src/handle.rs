@@ -1,46 +1,166 @@
 use cosmwasm_bignumber::{Decimal256, Uint256};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::{
-    attr, to_binary, Addr, CanonicalAddr, CosmosMsg, DepsMut, Env, MessageInfo, Response, WasmMsg,
+    attr, to_binary, Addr, Api, BankMsg, Binary, CanonicalAddr, Coin, CosmosMsg, Deps, DepsMut,
+    Env, Event, MessageInfo, Order, Response, StdResult, Storage, SubMsg, WasmMsg,
 };
-use cw20::Cw20ExecuteMsg;
-use terraswap::querier::query_token_balance;
+use cw20::{Cw20ExecuteMsg, Cw20QueryMsg, Expiration, TokenInfoResponse};
+use cw_storage_plus::Bound;
+use terraswap::asset::AssetInfo;
+use terraswap::querier::{query_balance, query_token_balance};
 
-use crate::state::{Config, StakerInfo, State, CONFIG, STAKER_INFO, STATE};
+use crate::events::{
+    distribute_event, staker_event, ATTR_ADDED, ATTR_AMOUNT, ATTR_ASSET, ATTR_BURNED,
+    ATTR_COUNTERPARTY, ATTR_DEPOSIT_FEE, ATTR_ERROR, ATTR_EXPIRES, ATTR_FEE_AMOUNT, ATTR_GAS_LIMIT,
+    ATTR_HOOK, ATTR_KEEPER, ATTR_OPERATOR, ATTR_PENALTY_AMOUNT, ATTR_RECEIPT_TOKEN, ATTR_RECIPIENT,
+    ATTR_REFERRAL_AMOUNT, ATTR_REFERRAL_RESERVE, ATTR_REFERRER, ATTR_REMOVED,
+    ATTR_REVERT_ON_FAILURE, ATTR_REWARDER, ATTR_REWARD_AMOUNT, ATTR_REWARD_RATE,
+    ATTR_REWARD_STREAM_END_TIME, ATTR_SENDER, ATTR_SPENDER, ATTR_STAKER, ATTR_TOKEN_ID,
+    ATTR_UPDATED, ATTR_VESTING_END_TIME, EVENT_ADD_HOOK, EVENT_APPROVE, EVENT_APPROVE_ALL,
+    EVENT_APPROVE_OPERATOR, EVENT_BOND, EVENT_BOND_POSITION, EVENT_CLAIM_POSITION_REWARDS,
+    EVENT_CLAIM_REWARDS, EVENT_DEPOSIT_REFERRAL_RESERVE, EVENT_DEPOSIT_REWARD, EVENT_KICK,
+    EVENT_NOTIFY_REWARD_AMOUNT, EVENT_RECOVER_UNDISTRIBUTED, EVENT_REGISTER_RECEIPT_TOKEN,
+    EVENT_REMOVE_HOOK, EVENT_REVOKE, EVENT_REVOKE_ALL, EVENT_REVOKE_OPERATOR,
+    EVENT_REWARDER_FAILED, EVENT_SET_KEEPER, EVENT_SET_REWARDER, EVENT_STAKE_CHANGED_HOOK_FAILED,
+    EVENT_SWEEP_TOKENS, EVENT_TRANSFER_NFT, EVENT_TRANSFER_POSITION_IN,
+    EVENT_TRANSFER_POSITION_OUT, EVENT_UNBOND, EVENT_UNBOND_POSITION, EVENT_UPDATE_ALLOWLIST,
+    EVENT_UPDATE_CONFIG, EVENT_WITHDRAW_VESTED, EVENT_WITHDRAW_VESTED_EARLY,
+};
+use crate::msg::{
+    BoostQueryMsg, BoostSource, Cw721ReceiveMsg, Cw721ReceiverExecuteMsg, EmissionCurve,
+    FarmStatus, RewarderExecuteMsg, ScheduleEntry, StakeChangedExecuteMsg, StakeChangedHookMsg,
+    StakedBalanceResponse, TotalStakedResponse, UndistributedPolicy, UpdateConfigMsg,
+};
+use crate::state::{
+    positions, read_owner_position_bond, read_owner_stake, read_position_ids, staker_infos, Config,
+    Hook, Position, PositionApproval, StakerInfo, State, VestingSchedule, ALLOWLIST, CONFIG, HOOKS,
+    MAX_DEPOSIT_FEE_RATE, MAX_HOOKS, MAX_REFERRAL_RATE, MAX_REWARD_FEE_RATE, NFT_OPERATORS,
+    OPERATORS, REFEREES, REFERRAL_EARNINGS, REFERRERS, STATE, VESTING_SCHEDULES,
+};
 use crate::ContractError;
 
+pub const STAKE_CHANGED_HOOK_REPLY_ID: u64 = 1;
+pub const REWARDER_REPLY_ID: u64 = 2;
+// prefix of the DistributeToStakers cursor once the stakers are done and the positions are distributed to
+pub const POSITION_CURSOR_PREFIX: &str = "position:";
+// gas available to the rewarder, so a failing rewarder cannot block the staking actions
+pub const REWARDER_GAS_LIMIT: u64 = 500_000;
+// gas available by default to the hooks allowed to fail
+pub const DEFAULT_HOOK_GAS_LIMIT: u64 = 300_000;
+
+// share of the bond amount that earns rewards without any boost, in percent
+const BOOST_BASE_WEIGHT: u64 = 40;
+
 pub fn bond(
     deps: DepsMut,
     env: Env,
     sender_addr: Addr,
     amount: Uint256,
+    referrer: Option<String>,
 ) -> Result<Response, ContractError> {
     let sender_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(sender_addr.as_str())?;
 
+    // Record the referrer of the staker once
+    if let Some(referrer) = referrer {
+        let referrer_addr: Addr = deps.api.addr_validate(&referrer)?;
+        let referrer_raw: CanonicalAddr = deps.api.addr_canonicalize(referrer_addr.as_str())?;
+        if REFERRERS
+            .may_load(deps.storage, sender_addr_raw.as_slice())?
+            .is_none()
+        {
+            validate_referrer(deps.as_ref(), &sender_addr_raw, &referrer_raw)?;
+            REFERRERS.save(deps.storage, sender_addr_raw.as_slice(), &referrer_raw)?;
+            REFEREES.save(
+                deps.storage,
+                (referrer_raw.as_slice(), sender_addr_raw.as_slice()),
+                &true,
+            )?;
+        }
+    }
+
     let config: Config = CONFIG.load(deps.storage)?;
     let mut state: State = STATE.load(deps.storage)?;
     let mut staker_info: StakerInfo =
-        match STAKER_INFO.may_load(deps.storage, sender_addr_raw.as_slice())? {
+        match staker_infos().may_load(deps.storage, sender_addr_raw.as_slice())? {
             Some(staker_info) => staker_info,
-            None => StakerInfo::default(),
+            None => {
+                state.staker_count += 1;
+                StakerInfo::default()
+            }
         };
 
+    let (amount, deposit_fee): (Uint256, Uint256) = apply_bond_restrictions(
+        deps.as_ref(),
+        &env,
+        &config,
+        &state,
+        &sender_addr_raw,
+        &staker_info,
+        amount,
+    )?;
+
     // Compute global reward & staker reward
+    let global_reward_index: Decimal256 = state.global_reward_index;
     compute_reward(&config, &mut state, env.block.time.seconds());
     compute_staker_reward(&state, &mut staker_info);
 
     // Increase bond_amount
+    let old_bond: Uint256 = staker_info.bond_amount;
     increase_bond_amount(&mut state, &mut staker_info, amount);
+    update_staker_weight(
+        deps.as_ref(),
+        &config,
+        &mut state,
+        &sender_addr,
+        &mut staker_info,
+    )?;
 
     // Store updated state with staker's staker_info
-    STAKER_INFO.save(deps.storage, sender_addr_raw.as_slice(), &staker_info)?;
+    staker_infos().save(deps.storage, sender_addr_raw.as_slice(), &staker_info)?;
     STATE.save(deps.storage, &state)?;
 
-    Ok(Response::new().add_attributes(vec![
-        attr("action", "bond"),
-        attr("owner", sender_addr),
-        attr("amount", amount.to_string()),
-    ]))
+    let position_bond: Uint256 = read_owner_position_bond(deps.storage, &sender_addr_raw)?;
+    Ok(Response::new()
+        .add_messages(deposit_fee_messages(deps.as_ref(), &config, deposit_fee)?)
+        .add_event(distribute_event(
+            deps.api,
+            &config,
+            global_reward_index,
+            &state,
+        )?)
+        .add_event(
+            staker_event(EVENT_BOND, &sender_addr, &staker_info, &state)
+                .add_attribute(ATTR_AMOUNT, amount.to_string())
+                .add_attribute(ATTR_DEPOSIT_FEE, deposit_fee.to_string()),
+        )
+        .add_messages(receipt_token_messages(
+            deps.as_ref(),
+            &config,
+            amount,
+            Cw20ExecuteMsg::Mint {
+                recipient: sender_addr.to_string(),
+                amount: amount.into(),
+            },
+        )?)
+        .add_submessages(rewarder_messages(
+            deps.as_ref(),
+            &config,
+            &sender_addr,
+            staker_info.bond_amount + position_bond,
+            staker_info.pending_reward,
+        )?)
+        .add_submessages(stake_changed_hook_messages(
+            deps.as_ref(),
+            &sender_addr,
+            old_bond + position_bond,
+            staker_info.bond_amount + position_bond,
+        )?)
+        .add_attributes(vec![
+            attr("action", "bond"),
+            attr("owner", sender_addr),
+            attr("amount", amount.to_string()),
+            attr("deposit_fee", deposit_fee.to_string()),
+        ]))
 }
 
 pub fn bond_hook(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
@@ -50,46 +170,607 @@ pub fn bond_hook(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response,
     let mut state: State = STATE.load(deps.storage)?;
 
     // Compare lp token balance to bond amount to obtain newly received LP tokens
-    let lp_token: Addr = deps.api.addr_humanize(&config.miaw_lp_token)?;
-    let lp_token_balance: Uint256 =
-        query_token_balance(&deps.querier, lp_token, env.contract.address)?.into();
-
-    let amount: Uint256 = lp_token_balance - state.total_bond_amount;
+    let amount: Uint256 = query_lp_surplus(deps.as_ref(), &env, &config, &state)?;
     if amount.is_zero() {
         return Err(ContractError::NothingToStake {});
     }
 
-    let mut staker_info: StakerInfo = STAKER_INFO.load(deps.storage, sender_addr_raw.as_slice())?;
+    let mut staker_info: StakerInfo =
+        staker_infos().load(deps.storage, sender_addr_raw.as_slice())?;
+
+    let (amount, deposit_fee): (Uint256, Uint256) = apply_bond_restrictions(
+        deps.as_ref(),
+        &env,
+        &config,
+        &state,
+        &sender_addr_raw,
+        &staker_info,
+        amount,
+    )?;
 
     // Compute global reward & staker reward
+    let global_reward_index: Decimal256 = state.global_reward_index;
     compute_reward(&config, &mut state, env.block.time.seconds());
     compute_staker_reward(&state, &mut staker_info);
 
     // Increase bond_amount
+    let old_bond: Uint256 = staker_info.bond_amount;
     increase_bond_amount(&mut state, &mut staker_info, amount);
+    update_staker_weight(
+        deps.as_ref(),
+        &config,
+        &mut state,
+        &info.sender,
+        &mut staker_info,
+    )?;
 
     // Store updated state with staker's staker_info
-    STAKER_INFO.save(deps.storage, sender_addr_raw.as_slice(), &staker_info)?;
+    staker_infos().save(deps.storage, sender_addr_raw.as_slice(), &staker_info)?;
     STATE.save(deps.storage, &state)?;
 
-    Ok(Response::new().add_attributes(vec![
-        attr("action", "bond_hook"),
-        attr("owner", info.sender),
-        attr("amount", amount.to_string()),
-    ]))
+    let position_bond: Uint256 = read_owner_position_bond(deps.storage, &sender_addr_raw)?;
+    Ok(Response::new()
+        .add_messages(deposit_fee_messages(deps.as_ref(), &config, deposit_fee)?)
+        .add_event(distribute_event(
+            deps.api,
+            &config,
+            global_reward_index,
+            &state,
+        )?)
+        .add_event(
+            staker_event(EVENT_BOND, &info.sender, &staker_info, &state)
+                .add_attribute(ATTR_AMOUNT, amount.to_string())
+                .add_attribute(ATTR_DEPOSIT_FEE, deposit_fee.to_string()),
+        )
+        .add_messages(receipt_token_messages(
+            deps.as_ref(),
+            &config,
+            amount,
+            Cw20ExecuteMsg::Mint {
+                recipient: info.sender.to_string(),
+                amount: amount.into(),
+            },
+        )?)
+        .add_submessages(rewarder_messages(
+            deps.as_ref(),
+            &config,
+            &info.sender,
+            staker_info.bond_amount + position_bond,
+            staker_info.pending_reward,
+        )?)
+        .add_submessages(stake_changed_hook_messages(
+            deps.as_ref(),
+            &info.sender,
+            old_bond + position_bond,
+            staker_info.bond_amount + position_bond,
+        )?)
+        .add_attributes(vec![
+            attr("action", "bond_hook"),
+            attr("owner", info.sender),
+            attr("amount", amount.to_string()),
+            attr("deposit_fee", deposit_fee.to_string()),
+        ]))
+}
+
+// enforce the bonding restrictions and split the deposit fee from the amount to bond
+pub fn apply_bond_restrictions(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    state: &State,
+    staker_addr_raw: &CanonicalAddr,
+    staker_info: &StakerInfo,
+    amount: Uint256,
+) -> Result<(Uint256, Uint256), ContractError> {
+    if config.farm_status(env.block.time.seconds()) != FarmStatus::Active {
+        return Err(ContractError::FarmNotActive {});
+    }
+
+    if config.allowlist_enabled && !ALLOWLIST.has(deps.storage, staker_addr_raw.as_slice()) {
+        return Err(ContractError::NotAllowlisted {});
+    }
+
+    let deposit_fee: Uint256 = amount * config.deposit_fee_rate;
+    let amount: Uint256 = amount - deposit_fee;
+
+    // the cap per staker covers the bond of its positions too
+    if !config.max_bond_per_staker.is_zero()
+        && staker_info.bond_amount
+            + read_owner_position_bond(deps.storage, staker_addr_raw)?
+            + amount
+            > config.max_bond_per_staker
+    {
+        return Err(ContractError::StakerBondCapExceeded {});
+    }
+
+    if !config.max_total_bond.is_zero() && state.total_bond_amount + amount > config.max_total_bond
+    {
+        return Err(ContractError::TotalBondCapExceeded {});
+    }
+
+    Ok((amount, deposit_fee))
+}
+
+fn deposit_fee_messages(
+    deps: Deps,
+    config: &Config,
+    deposit_fee: Uint256,
+) -> StdResult<Vec<CosmosMsg>> {
+    let treasury: &CanonicalAddr = match &config.treasury {
+        Some(treasury) if !deposit_fee.is_zero() => treasury,
+        _ => return Ok(vec![]),
+    };
+
+    Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: deps.api.addr_humanize(&config.miaw_lp_token)?.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: deps.api.addr_humanize(treasury)?.to_string(),
+            amount: deposit_fee.into(),
+        })?,
+        funds: vec![],
+    })])
+}
+
+fn receipt_token_messages(
+    deps: Deps,
+    config: &Config,
+    amount: Uint256,
+    msg: Cw20ExecuteMsg,
+) -> StdResult<Vec<CosmosMsg>> {
+    let receipt_token: &CanonicalAddr = match &config.receipt_token {
+        Some(receipt_token) if !amount.is_zero() => receipt_token,
+        _ => return Ok(vec![]),
+    };
+
+    Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: deps.api.addr_humanize(receipt_token)?.to_string(),
+        msg: to_binary(&msg)?,
+        funds: vec![],
+    })])
+}
+
+pub fn register_receipt_token(
+    deps: DepsMut,
+    info: MessageInfo,
+    receipt_token: String,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Existing bonds would not be backed by receipts
+    let state: State = STATE.load(deps.storage)?;
+    if config.receipt_token.is_some() || !state.total_bond_amount.is_zero() {
+        return Err(ContractError::ReceiptTokenRegistrationClosed {});
+    }
+
+    config.receipt_token = Some(deps.api.addr_canonicalize(&receipt_token)?);
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new(EVENT_REGISTER_RECEIPT_TOKEN)
+                .add_attribute(ATTR_RECEIPT_TOKEN, receipt_token.clone()),
+        )
+        .add_attributes(vec![
+            attr("action", "register_receipt_token"),
+            attr("receipt_token", receipt_token),
+        ]))
+}
+
+pub fn set_rewarder(
+    deps: DepsMut,
+    info: MessageInfo,
+    rewarder: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.rewarder = match &rewarder {
+        Some(rewarder) => Some(deps.api.addr_canonicalize(rewarder)?),
+        None => None,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    let rewarder: String = rewarder.unwrap_or_default();
+    Ok(Response::new()
+        .add_event(Event::new(EVENT_SET_REWARDER).add_attribute(ATTR_REWARDER, rewarder.clone()))
+        .add_attributes(vec![
+            attr("action", "set_rewarder"),
+            attr("rewarder", rewarder),
+        ]))
+}
+
+pub fn set_keeper(
+    deps: DepsMut,
+    info: MessageInfo,
+    keeper: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.keeper = match &keeper {
+        Some(keeper) => Some(deps.api.addr_canonicalize(keeper)?),
+        None => None,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    let keeper: String = keeper.unwrap_or_default();
+    Ok(Response::new()
+        .add_event(Event::new(EVENT_SET_KEEPER).add_attribute(ATTR_KEEPER, keeper.clone()))
+        .add_attributes(vec![attr("action", "set_keeper"), attr("keeper", keeper)]))
+}
+
+// notify the rewarder, replying on error so a failing rewarder is skipped
+fn rewarder_messages(
+    deps: Deps,
+    config: &Config,
+    staker: &Addr,
+    new_bond: Uint256,
+    pending_miaw: Uint256,
+) -> StdResult<Vec<SubMsg>> {
+    let rewarder: &CanonicalAddr = match &config.rewarder {
+        Some(rewarder) => rewarder,
+        None => return Ok(vec![]),
+    };
+
+    let execute_msg = WasmMsg::Execute {
+        contract_addr: deps.api.addr_humanize(rewarder)?.to_string(),
+        msg: to_binary(&RewarderExecuteMsg::OnMiawReward {
+            staker: staker.to_string(),
+            new_bond,
+            pending_miaw,
+        })?,
+        funds: vec![],
+    };
+
+    Ok(vec![
+        SubMsg::reply_on_error(execute_msg, REWARDER_REPLY_ID).with_gas_limit(REWARDER_GAS_LIMIT)
+    ])
+}
+
+pub fn rewarder_failed(error: String) -> Result<Response, ContractError> {
+    Ok(Response::new()
+        .add_event(Event::new(EVENT_REWARDER_FAILED).add_attribute(ATTR_ERROR, error.clone()))
+        .add_attributes(vec![
+            attr("action", "rewarder_failed"),
+            attr("error", error),
+        ]))
+}
+
+pub fn add_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+    revert_on_failure: Option<bool>,
+    gas_limit: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let hook_addr: Addr = deps.api.addr_validate(&addr)?;
+    let hook_raw: CanonicalAddr = deps.api.addr_canonicalize(hook_addr.as_str())?;
+    if HOOKS.has(deps.storage, hook_raw.as_slice()) {
+        return Err(ContractError::HookAlreadyRegistered {});
+    }
+    if HOOKS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .count()
+        >= MAX_HOOKS
+    {
+        return Err(ContractError::TooManyHooks { max: MAX_HOOKS });
+    }
+
+    // a hook allowed to fail must not use up the gas of the staking action
+    let revert_on_failure: bool = revert_on_failure.unwrap_or(true);
+    let gas_limit: Option<u64> = match gas_limit {
+        None if !revert_on_failure => Some(DEFAULT_HOOK_GAS_LIMIT),
+        gas_limit => gas_limit,
+    };
+    HOOKS.save(
+        deps.storage,
+        hook_raw.as_slice(),
+        &Hook {
+            revert_on_failure,
+            gas_limit,
+        },
+    )?;
+
+    let gas_limit: String = gas_limit
+        .map(|gas_limit| gas_limit.to_string())
+        .unwrap_or_default();
+    Ok(Response::new()
+        .add_event(
+            Event::new(EVENT_ADD_HOOK)
+                .add_attribute(ATTR_HOOK, hook_addr.to_string())
+                .add_attribute(ATTR_REVERT_ON_FAILURE, revert_on_failure.to_string())
+                .add_attribute(ATTR_GAS_LIMIT, gas_limit.clone()),
+        )
+        .add_attributes(vec![
+            attr("action", "add_hook"),
+            attr("hook", hook_addr),
+            attr("revert_on_failure", revert_on_failure.to_string()),
+            attr("gas_limit", gas_limit),
+        ]))
+}
+
+pub fn remove_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let hook_addr: Addr = deps.api.addr_validate(&addr)?;
+    let hook_raw: CanonicalAddr = deps.api.addr_canonicalize(hook_addr.as_str())?;
+    if !HOOKS.has(deps.storage, hook_raw.as_slice()) {
+        return Err(ContractError::HookNotRegistered {});
+    }
+    HOOKS.remove(deps.storage, hook_raw.as_slice());
+
+    Ok(Response::new()
+        .add_event(Event::new(EVENT_REMOVE_HOOK).add_attribute(ATTR_HOOK, hook_addr.to_string()))
+        .add_attributes(vec![attr("action", "remove_hook"), attr("hook", hook_addr)]))
+}
+
+// notify the hook contracts of a bond change, the hooks allowed to fail
+// reply on error within their gas limit so the staking action goes through
+fn stake_changed_hook_messages(
+    deps: Deps,
+    staker: &Addr,
+    old_bond: Uint256,
+    new_bond: Uint256,
+) -> StdResult<Vec<SubMsg>> {
+    if old_bond == new_bond {
+        return Ok(vec![]);
+    }
+
+    let msg: Binary = to_binary(&StakeChangedExecuteMsg::StakeChangedHook(
+        StakeChangedHookMsg {
+            addr: staker.to_string(),
+            old_bond,
+            new_bond,
+        },
+    ))?;
+
+    HOOKS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (k, hook) = item?;
+            let execute_msg = WasmMsg::Execute {
+                contract_addr: deps.api.addr_humanize(&CanonicalAddr::from(k))?.to_string(),
+                msg: msg.clone(),
+                funds: vec![],
+            };
+
+            let sub_msg: SubMsg = if hook.revert_on_failure {
+                SubMsg::new(execute_msg)
+            } else {
+                SubMsg::reply_on_error(execute_msg, STAKE_CHANGED_HOOK_REPLY_ID)
+            };
+            Ok(match hook.gas_limit {
+                Some(gas_limit) => sub_msg.with_gas_limit(gas_limit),
+                None => sub_msg,
+            })
+        })
+        .collect::<StdResult<Vec<SubMsg>>>()
+}
+
+pub fn stake_changed_hook_failed(error: String) -> Result<Response, ContractError> {
+    Ok(Response::new()
+        .add_event(
+            Event::new(EVENT_STAKE_CHANGED_HOOK_FAILED).add_attribute(ATTR_ERROR, error.clone()),
+        )
+        .add_attributes(vec![
+            attr("action", "stake_changed_hook_failed"),
+            attr("error", error),
+        ]))
+}
+
+pub fn update_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for addr in add.iter() {
+        let addr_raw: CanonicalAddr = deps.api.addr_canonicalize(addr)?;
+        ALLOWLIST.save(deps.storage, addr_raw.as_slice(), &true)?;
+    }
+
+    for addr in remove.iter() {
+        let addr_raw: CanonicalAddr = deps.api.addr_canonicalize(addr)?;
+        ALLOWLIST.remove(deps.storage, addr_raw.as_slice());
+    }
+
+    Ok(Response::new()
+        .add_event(
+            Event::new(EVENT_UPDATE_ALLOWLIST)
+                .add_attribute(ATTR_ADDED, add.join(","))
+                .add_attribute(ATTR_REMOVED, remove.join(",")),
+        )
+        .add_attributes(vec![
+            attr("action", "update_allowlist"),
+            attr("added", add.len().to_string()),
+            attr("removed", remove.len().to_string()),
+        ]))
+}
+
+pub fn deposit_reward(
+    deps: DepsMut,
+    env: Env,
+    sender_addr: Addr,
+    amount: Uint256,
+) -> Result<Response, ContractError> {
+    let current_time: u64 = env.block.time.seconds();
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut state: State = STATE.load(deps.storage)?;
+
+    // Settle the rewards emitted until now before adding the deposit
+    let global_reward_index: Decimal256 = state.global_reward_index;
+    compute_reward(&config, &mut state, current_time);
+
+    // only the owner restarts the stream window of an active stream
+    let is_owner: bool = config.owner == deps.api.addr_canonicalize(sender_addr.as_str())?;
+    add_reward(&config, &mut state, current_time, amount, is_owner);
+
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_event(distribute_event(
+            deps.api,
+            &config,
+            global_reward_index,
+            &state,
+        )?)
+        .add_event(
+            Event::new(EVENT_DEPOSIT_REWARD)
+                .add_attribute(ATTR_SENDER, sender_addr.to_string())
+                .add_attribute(ATTR_AMOUNT, amount.to_string())
+                .add_attribute(
+                    ATTR_REWARD_STREAM_END_TIME,
+                    state.reward_stream_end_time.to_string(),
+                ),
+        )
+        .add_attributes(vec![
+            attr("action", "deposit_reward"),
+            attr("sender", sender_addr),
+            attr("amount", amount.to_string()),
+        ]))
+}
+
+pub fn deposit_referral_reserve(
+    deps: DepsMut,
+    sender_addr: Addr,
+    amount: Uint256,
+) -> Result<Response, ContractError> {
+    let mut state: State = STATE.load(deps.storage)?;
+    state.referral_reserve += amount;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new(EVENT_DEPOSIT_REFERRAL_RESERVE)
+                .add_attribute(ATTR_SENDER, sender_addr.to_string())
+                .add_attribute(ATTR_AMOUNT, amount.to_string())
+                .add_attribute(ATTR_REFERRAL_RESERVE, state.referral_reserve.to_string()),
+        )
+        .add_attributes(vec![
+            attr("action", "deposit_referral_reserve"),
+            attr("sender", sender_addr),
+            attr("amount", amount.to_string()),
+        ]))
+}
+
+pub fn notify_reward_amount(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint256,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let current_time: u64 = env.block.time.seconds();
+    let mut state: State = STATE.load(deps.storage)?;
+    let global_reward_index: Decimal256 = state.global_reward_index;
+    compute_reward(&config, &mut state, current_time);
+
+    // The notified rewards must be held on top of the ones already owed
+    let miaw_token: Addr = deps.api.addr_humanize(&config.miaw_token)?;
+    let balance: Uint256 =
+        query_token_balance(&deps.querier, miaw_token, env.contract.address)?.into();
+    if balance < reserved_reward_amount(&config, &state) + amount {
+        return Err(ContractError::InsufficientRewardBalance {});
+    }
+
+    add_reward(&config, &mut state, current_time, amount, true);
+
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_event(distribute_event(
+            deps.api,
+            &config,
+            global_reward_index,
+            &state,
+        )?)
+        .add_event(
+            Event::new(EVENT_NOTIFY_REWARD_AMOUNT)
+                .add_attribute(ATTR_AMOUNT, amount.to_string())
+                .add_attribute(ATTR_REWARD_RATE, state.reward_rate.to_string())
+                .add_attribute(
+                    ATTR_REWARD_STREAM_END_TIME,
+                    state.reward_stream_end_time.to_string(),
+                ),
+        )
+        .add_attributes(vec![
+            attr("action", "notify_reward_amount"),
+            attr("amount", amount.to_string()),
+            attr("reward_rate", state.reward_rate.to_string()),
+        ]))
+}
+
+// distribute the added rewards at once, or spread them again with the left
+// stream over a fresh duration
+fn add_reward(
+    config: &Config,
+    state: &mut State,
+    current_time: u64,
+    amount: Uint256,
+    extend_stream: bool,
+) {
+    if config.reward_deposit_duration == 0 {
+        distribute_reward(config, state, amount);
+        return;
+    }
+
+    state.reward_stream_amount += amount;
+
+    // an active stream keeps its end time unless the deposit may extend it,
+    // so that small deposits cannot dilute the reward rate
+    let remaining_duration: u64 = if !extend_stream && state.reward_stream_end_time > current_time {
+        state.reward_stream_end_time - current_time
+    } else {
+        state.reward_stream_end_time = current_time + config.reward_deposit_duration;
+        config.reward_deposit_duration
+    };
+
+    state.reward_rate = Decimal256::from_uint256(state.reward_stream_amount)
+        / Decimal256::from_uint256(remaining_duration);
 }
 
 pub fn unbond(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    staker: Option<String>,
     amount: Option<Uint256>,
 ) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
-    let sender_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let staker_addr: Addr = resolve_staker(deps.as_ref(), &env, &info.sender, staker)?;
+    let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
 
     let mut state: State = STATE.load(deps.storage)?;
-    let mut staker_info: StakerInfo = STAKER_INFO.load(deps.storage, sender_addr_raw.as_slice())?;
+    let mut staker_info: StakerInfo =
+        staker_infos().load(deps.storage, staker_addr_raw.as_slice())?;
 
     let amount_to_unbond: Uint256 = if let Some(amount) = amount {
         if staker_info.bond_amount < amount {
@@ -102,35 +783,80 @@ pub fn unbond(
     };
 
     // Compute global reward & staker reward
+    let global_reward_index: Decimal256 = state.global_reward_index;
     compute_reward(&config, &mut state, env.block.time.seconds());
     compute_staker_reward(&state, &mut staker_info);
 
     // Decrease bond_amount
+    let old_bond: Uint256 = staker_info.bond_amount;
     decrease_bond_amount(&mut state, &mut staker_info, amount_to_unbond);
+    update_staker_weight(
+        deps.as_ref(),
+        &config,
+        &mut state,
+        &staker_addr,
+        &mut staker_info,
+    )?;
 
     // Store or remove updated rewards info
     // depends on the left pending reward and bond amount
     if staker_info.pending_reward.is_zero() && staker_info.bond_amount.is_zero() {
-        STAKER_INFO.remove(deps.storage, sender_addr_raw.as_slice());
+        staker_infos().remove(deps.storage, staker_addr_raw.as_slice())?;
+        state.staker_count -= 1;
+        release_reward_dust(&mut state, &staker_info);
     } else {
-        STAKER_INFO.save(deps.storage, sender_addr_raw.as_slice(), &staker_info)?;
+        staker_infos().save(deps.storage, staker_addr_raw.as_slice(), &staker_info)?;
     }
 
     // Store updated state
     STATE.save(deps.storage, &state)?;
 
+    let position_bond: Uint256 = read_owner_position_bond(deps.storage, &staker_addr_raw)?;
     Ok(Response::new()
+        .add_event(distribute_event(
+            deps.api,
+            &config,
+            global_reward_index,
+            &state,
+        )?)
+        .add_event(
+            staker_event(EVENT_UNBOND, &staker_addr, &staker_info, &state)
+                .add_attribute(ATTR_SENDER, info.sender.to_string())
+                .add_attribute(ATTR_AMOUNT, amount_to_unbond.to_string()),
+        )
+        .add_messages(receipt_token_messages(
+            deps.as_ref(),
+            &config,
+            amount_to_unbond,
+            Cw20ExecuteMsg::BurnFrom {
+                owner: staker_addr.to_string(),
+                amount: amount_to_unbond.into(),
+            },
+        )?)
         .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: deps.api.addr_humanize(&config.miaw_lp_token)?.to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: info.sender.to_string(),
+                recipient: staker_addr.to_string(),
                 amount: amount_to_unbond.into(),
             })?,
             funds: vec![],
         })])
+        .add_submessages(rewarder_messages(
+            deps.as_ref(),
+            &config,
+            &staker_addr,
+            staker_info.bond_amount + position_bond,
+            staker_info.pending_reward,
+        )?)
+        .add_submessages(stake_changed_hook_messages(
+            deps.as_ref(),
+            &staker_addr,
+            old_bond + position_bond,
+            staker_info.bond_amount + position_bond,
+        )?)
         .add_attributes(vec![
             attr("action", "unbond"),
-            attr("owner", info.sender),
+            attr("owner", staker_addr),
             attr("amount", amount_to_unbond.to_string()),
         ]))
 }
@@ -139,89 +865,2259 @@ pub fn claim_rewards(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    staker: Option<String>,
 ) -> Result<Response, ContractError> {
-    let sender_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let staker_addr: Addr = resolve_staker(deps.as_ref(), &env, &info.sender, staker)?;
+    let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
 
     let config: Config = CONFIG.load(deps.storage)?;
     let mut state: State = STATE.load(deps.storage)?;
-    let mut staker_info: StakerInfo = STAKER_INFO.load(deps.storage, sender_addr_raw.as_slice())?;
+    let mut staker_info: StakerInfo =
+        staker_infos().load(deps.storage, staker_addr_raw.as_slice())?;
 
     // Compute global reward & staker reward
+    let global_reward_index: Decimal256 = state.global_reward_index;
     compute_reward(&config, &mut state, env.block.time.seconds());
     compute_staker_reward(&state, &mut staker_info);
 
-    let amount: Uint256 = staker_info.pending_reward;
-    staker_info.pending_reward = Uint256::zero();
+    update_staker_weight(
+        deps.as_ref(),
+        &config,
+        &mut state,
+        &staker_addr,
+        &mut staker_info,
+    )?;
 
-    // Store or remove updated rewards info
-    // depends on the left pending reward and bond amount
-    if staker_info.bond_amount.is_zero() {
-        STAKER_INFO.remove(deps.storage, sender_addr_raw.as_slice());
-    } else {
-        STAKER_INFO.save(deps.storage, sender_addr_raw.as_slice(), &staker_info)?;
-    }
+    let pending_miaw: Uint256 = staker_info.pending_reward;
+    let claim: Claim = settle_claim(
+        deps.storage,
+        &env,
+        &config,
+        &mut state,
+        &staker_addr_raw,
+        &mut staker_info,
+    )?;
 
     // Store updated state
     STATE.save(deps.storage, &state)?;
 
-    Ok(Response::new()
-        .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: deps.api.addr_humanize(&config.miaw_token)?.to_string(),
+    let miaw_token: Addr = deps.api.addr_humanize(&config.miaw_token)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes = vec![
+        attr("action", "withdraw"),
+        attr("owner", staker_addr.to_string()),
+        attr("amount", claim.amount.to_string()),
+        attr("fee_amount", claim.fee_amount.to_string()),
+    ];
+    match claim.vesting_end_time {
+        Some(end_time) => attributes.push(attr("vesting_end_time", end_time.to_string())),
+        None => messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: miaw_token.to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: info.sender.to_string(),
-                amount: amount.into(),
+                recipient: staker_addr.to_string(),
+                amount: claim.amount.into(),
             })?,
             funds: vec![],
-        })])
-        .add_attributes(vec![
-            attr("action", "withdraw"),
-            attr("owner", info.sender),
-            attr("amount", amount.to_string()),
-        ]))
+        })),
+    }
+    messages.extend(reward_fee_messages(
+        deps.as_ref(),
+        &config,
+        &miaw_token,
+        claim.fee_amount,
+    )?);
+    let rewarder_messages: Vec<SubMsg> = rewarder_messages(
+        deps.as_ref(),
+        &config,
+        &staker_addr,
+        staker_info.bond_amount + read_owner_position_bond(deps.storage, &staker_addr_raw)?,
+        pending_miaw,
+    )?;
+
+    if let Some((referrer_raw, referral_amount)) = &claim.referral {
+        attributes.push(attr(
+            "referrer",
+            deps.api.addr_humanize(referrer_raw)?.to_string(),
+        ));
+        attributes.push(attr("referral_amount", referral_amount.to_string()));
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(rewarder_messages)
+        .add_attributes(attributes)
+        .add_event(distribute_event(
+            deps.api,
+            &config,
+            global_reward_index,
+            &state,
+        )?)
+        .add_event(
+            claim_event(
+                deps.api,
+                EVENT_CLAIM_REWARDS,
+                &staker_addr,
+                &staker_info,
+                &state,
+                &claim,
+            )?
+            .add_attribute(ATTR_SENDER, info.sender.to_string()),
+        ))
 }
 
-fn increase_bond_amount(state: &mut State, staker_info: &mut StakerInfo, amount: Uint256) {
-    state.total_bond_amount += amount;
-    staker_info.bond_amount += amount;
+// rewards paid out to a staker by a claim
+struct Claim {
+    // net of the reward fee
+    amount: Uint256,
+    fee_amount: Uint256,
+    referral: Option<(CanonicalAddr, Uint256)>,
+    // set when the amount is locked in a vesting schedule instead of transferred
+    vesting_end_time: Option<u64>,
 }
 
-fn decrease_bond_amount(state: &mut State, staker_info: &mut StakerInfo, amount: Uint256) {
-    state.total_bond_amount = state.total_bond_amount - amount;
-    staker_info.bond_amount = staker_info.bond_amount - amount;
+// split a claimed reward into the net payout and the protocol fee, the referral
+// reward of a referred staker is paid on top of it from the referral reserve
+pub fn split_claim_amount(
+    config: &Config,
+    state: &State,
+    amount: Uint256,
+    referred: bool,
+) -> (Uint256, Uint256, Uint256) {
+    let referral_amount: Uint256 = if referred {
+        std::cmp::min(amount * config.referral_rate, state.referral_reserve)
+    } else {
+        Uint256::zero()
+    };
+    let fee_amount: Uint256 = amount * config.reward_fee_rate;
+
+    (amount - fee_amount, fee_amount, referral_amount)
 }
 
-// compute distributed rewards and update global reward index
-pub fn compute_reward(config: &Config, state: &mut State, current_time: u64) {
-    if state.total_bond_amount.is_zero() {
-        state.last_distributed = current_time;
-        return;
-    }
+// pay out the pending reward of a settled staker, allocating the referral reward,
+// splitting the fee and locking the payout when vesting is enabled
+fn settle_claim(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    state: &mut State,
+    staker_addr_raw: &CanonicalAddr,
+    staker_info: &mut StakerInfo,
+) -> StdResult<Claim> {
+    let amount: Uint256 = staker_info.pending_reward;
+    staker_info.pending_reward = Uint256::zero();
 
+    // Store or remove updated rewards info
+    // depends on the left pending reward and bond amount
+    if staker_info.bond_amount.is_zero() {
+        staker_infos().remove(storage, staker_addr_raw.as_slice())?;
+        state.staker_count -= 1;
+        release_reward_dust(state, staker_info);
+    } else {
+        staker_infos().save(storage, staker_addr_raw.as_slice(), staker_info)?;
+    }
+
+    pay_claim(storage, env, config, state, staker_addr_raw, amount)
+}
+
+// pay out the pending reward of a settled position to its owner, burning the emptied position,
+// returns the claim and whether the position was burned
+fn settle_position_claim(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    state: &mut State,
+    token_id: &str,
+    position: &mut Position,
+) -> StdResult<(Claim, bool)> {
+    let amount: Uint256 = position.staker_info.pending_reward;
+    position.staker_info.pending_reward = Uint256::zero();
+    let burned: bool = save_or_burn_position(storage, state, token_id, position)?;
+
+    let claim: Claim = pay_claim(storage, env, config, state, &position.owner, amount)?;
+    Ok((claim, burned))
+}
+
+// split a claimed reward between the staker, its referrer and the fee collector,
+// locking the payout of the staker when vesting is enabled
+fn pay_claim(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    state: &mut State,
+    staker_addr_raw: &CanonicalAddr,
+    amount: Uint256,
+) -> StdResult<Claim> {
+    // Fund the referral reward from the reserve, it stays owed to the referrer
+    let referrer_raw: Option<CanonicalAddr> =
+        REFERRERS.may_load(storage, staker_addr_raw.as_slice())?;
+    let (net_amount, fee_amount, referral_amount) =
+        split_claim_amount(config, state, amount, referrer_raw.is_some());
+    let mut referral: Option<(CanonicalAddr, Uint256)> = None;
+    if let Some(referrer_raw) = referrer_raw {
+        if !referral_amount.is_zero() {
+            state.referral_reserve = state.referral_reserve - referral_amount;
+            state.cumulative_reward_distributed += referral_amount;
+            allocate_referral_reward(storage, state, &referrer_raw, referral_amount)?;
+            referral = Some((referrer_raw, referral_amount));
+        }
+    }
+    state.cumulative_reward_claimed += amount;
+    let amount: Uint256 = net_amount;
+
+    // Lock the rewards in a vesting schedule instead of transferring them,
+    // the claims of the same block share one schedule
+    let mut vesting_end_time: Option<u64> = None;
+    if config.vesting_duration > 0 {
+        let current_time: u64 = env.block.time.seconds();
+        let end_time: u64 = current_time + config.vesting_duration;
+        if !amount.is_zero() {
+            let mut schedules: Vec<VestingSchedule> = VESTING_SCHEDULES
+                .may_load(storage, staker_addr_raw.as_slice())?
+                .unwrap_or_default();
+            match schedules.last_mut() {
+                Some(schedule)
+                    if schedule.start_time == current_time && schedule.end_time == end_time =>
+                {
+                    schedule.amount += amount;
+                }
+                _ => schedules.push(VestingSchedule {
+                    start_time: current_time,
+                    end_time,
+                    amount,
+                    withdrawn_amount: Uint256::zero(),
+                }),
+            }
+            VESTING_SCHEDULES.save(storage, staker_addr_raw.as_slice(), &schedules)?;
+            state.vesting_amount += amount;
+        }
+        vesting_end_time = Some(end_time);
+    }
+
+    Ok(Claim {
+        amount,
+        fee_amount,
+        referral,
+        vesting_end_time,
+    })
+}
+
+fn claim_event(
+    api: &dyn Api,
+    ty: &str,
+    staker: &Addr,
+    staker_info: &StakerInfo,
+    state: &State,
+    claim: &Claim,
+) -> StdResult<Event> {
+    let mut event: Event = staker_event(ty, staker, staker_info, state)
+        .add_attribute(ATTR_AMOUNT, claim.amount.to_string())
+        .add_attribute(ATTR_FEE_AMOUNT, claim.fee_amount.to_string());
+    if let Some(end_time) = claim.vesting_end_time {
+        event = event.add_attribute(ATTR_VESTING_END_TIME, end_time.to_string());
+    }
+    if let Some((referrer_raw, referral_amount)) = &claim.referral {
+        event = event
+            .add_attribute(ATTR_REFERRER, api.addr_humanize(referrer_raw)?.to_string())
+            .add_attribute(ATTR_REFERRAL_AMOUNT, referral_amount.to_string());
+    }
+
+    Ok(event)
+}
+
+fn reward_fee_messages(
+    deps: Deps,
+    config: &Config,
+    miaw_token: &Addr,
+    fee_amount: Uint256,
+) -> StdResult<Vec<CosmosMsg>> {
+    let fee_collector: &CanonicalAddr = match &config.fee_collector {
+        Some(fee_collector) if !fee_amount.is_zero() => fee_collector,
+        _ => return Ok(vec![]),
+    };
+
+    Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: miaw_token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: deps.api.addr_humanize(fee_collector)?.to_string(),
+            amount: fee_amount.into(),
+        })?,
+        funds: vec![],
+    })])
+}
+
+pub fn distribute_to_stakers(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let sender_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if config.owner != sender_addr_raw && config.keeper != Some(sender_addr_raw) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut state: State = STATE.load(deps.storage)?;
+
+    // the positions follow the stakers, behind a prefixed cursor
+    let (start_after, position_start_after): (Option<CanonicalAddr>, Option<String>) =
+        match start_after {
+            Some(cursor) => match cursor.strip_prefix(POSITION_CURSOR_PREFIX) {
+                Some(token_id) => (None, Some(token_id.to_string())),
+                None => {
+                    let addr: Addr = deps
+                        .api
+                        .addr_validate(&cursor)
+                        .map_err(|_| ContractError::InvalidCursor {})?;
+                    (Some(deps.api.addr_canonicalize(addr.as_str())?), None)
+                }
+            },
+            None => (None, None),
+        };
+    let limit = limit.unwrap_or(config.default_limit).min(config.max_limit) as usize;
+    let start: Option<Bound> = start_after.map(|addr| Bound::exclusive(addr.as_slice()));
+
+    // Collect the keys first, referral rewards may update stakers of the batch
+    let stakers: Vec<CanonicalAddr> = if position_start_after.is_some() {
+        vec![]
+    } else {
+        staker_infos()
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| Ok(CanonicalAddr::from(item?.0)))
+            .collect::<StdResult<Vec<CanonicalAddr>>>()?
+    };
+    let token_ids: Vec<String> = read_position_ids(
+        deps.storage,
+        None,
+        position_start_after,
+        limit - stakers.len(),
+    )?;
+
+    // Compute global reward
+    let global_reward_index: Decimal256 = state.global_reward_index;
+    compute_reward(&config, &mut state, env.block.time.seconds());
+
+    let miaw_token: Addr = deps.api.addr_humanize(&config.miaw_token)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut submessages: Vec<SubMsg> = vec![];
+    let mut total_amount: Uint256 = Uint256::zero();
+    let mut total_fee_amount: Uint256 = Uint256::zero();
+    let mut events: Vec<Event> = vec![];
+    for staker_addr_raw in stakers.iter() {
+        let staker_addr: Addr = deps.api.addr_humanize(staker_addr_raw)?;
+        let mut staker_info: StakerInfo =
+            staker_infos().load(deps.storage, staker_addr_raw.as_slice())?;
+        compute_staker_reward(&state, &mut staker_info);
+        update_staker_weight(
+            deps.as_ref(),
+            &config,
+            &mut state,
+            &staker_addr,
+            &mut staker_info,
+        )?;
+
+        let pending_miaw: Uint256 = staker_info.pending_reward;
+        let claim: Claim = settle_claim(
+            deps.storage,
+            &env,
+            &config,
+            &mut state,
+            staker_addr_raw,
+            &mut staker_info,
+        )?;
+        total_amount += claim.amount;
+        total_fee_amount += claim.fee_amount;
+        events.push(claim_event(
+            deps.api,
+            EVENT_CLAIM_REWARDS,
+            &staker_addr,
+            &staker_info,
+            &state,
+            &claim,
+        )?);
+
+        if claim.vesting_end_time.is_none() && !claim.amount.is_zero() {
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: miaw_token.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: staker_addr.to_string(),
+                    amount: claim.amount.into(),
+                })?,
+                funds: vec![],
+            }));
+        }
+        submessages.extend(rewarder_messages(
+            deps.as_ref(),
+            &config,
+            &staker_addr,
+            staker_info.bond_amount + read_owner_position_bond(deps.storage, staker_addr_raw)?,
+            pending_miaw,
+        )?);
+    }
+    for token_id in token_ids.iter() {
+        let mut position: Position = positions().load(deps.storage, token_id)?;
+        let owner_addr: Addr = deps.api.addr_humanize(&position.owner)?;
+        compute_staker_reward(&state, &mut position.staker_info);
+        update_position_weight(&config, &mut state, &mut position);
+
+        let pending_miaw: Uint256 = position.staker_info.pending_reward;
+        let (claim, burned): (Claim, bool) = settle_position_claim(
+            deps.storage,
+            &env,
+            &config,
+            &mut state,
+            token_id,
+            &mut position,
+        )?;
+        total_amount += claim.amount;
+        total_fee_amount += claim.fee_amount;
+        events.push(
+            claim_event(
+                deps.api,
+                EVENT_CLAIM_POSITION_REWARDS,
+                &owner_addr,
+                &position.staker_info,
+                &state,
+                &claim,
+            )?
+            .add_attribute(ATTR_TOKEN_ID, token_id.to_string())
+            .add_attribute(ATTR_BURNED, burned.to_string()),
+        );
+
+        if claim.vesting_end_time.is_none() && !claim.amount.is_zero() {
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: miaw_token.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: owner_addr.to_string(),
+                    amount: claim.amount.into(),
+                })?,
+                funds: vec![],
+            }));
+        }
+        submessages.extend(rewarder_messages(
+            deps.as_ref(),
+            &config,
+            &owner_addr,
+            read_owner_stake(deps.storage, &position.owner)?,
+            pending_miaw,
+        )?);
+    }
+    messages.extend(reward_fee_messages(
+        deps.as_ref(),
+        &config,
+        &miaw_token,
+        total_fee_amount,
+    )?);
+
+    STATE.save(deps.storage, &state)?;
+
+    // a full batch may be followed by more stakers or positions
+    let next_cursor: String = if stakers.len() + token_ids.len() < limit {
+        String::new()
+    } else if let Some(last) = token_ids.last() {
+        format!("{}{}", POSITION_CURSOR_PREFIX, last)
+    } else if let Some(last) = stakers.last() {
+        deps.api.addr_humanize(last)?.to_string()
+    } else {
+        String::new()
+    };
+
+    events.insert(
+        0,
+        distribute_event(deps.api, &config, global_reward_index, &state)?,
+    );
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(submessages)
+        .add_events(events)
+        .add_attributes(vec![
+            attr("action", "distribute_to_stakers"),
+            attr("staker_count", stakers.len().to_string()),
+            attr("position_count", token_ids.len().to_string()),
+            attr("amount", total_amount.to_string()),
+            attr("fee_amount", total_fee_amount.to_string()),
+            attr("next_cursor", next_cursor),
+        ]))
+}
+
+// the sender acts for itself unless it is an operator approved by the staker
+fn resolve_staker(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    staker: Option<String>,
+) -> Result<Addr, ContractError> {
+    let staker_addr: Addr = match staker {
+        Some(staker) => deps.api.addr_validate(&staker)?,
+        None => return Ok(sender.clone()),
+    };
+    if staker_addr == *sender {
+        return Ok(staker_addr);
+    }
+
+    let staker_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    let operator_raw: CanonicalAddr = deps.api.addr_canonicalize(sender.as_str())?;
+    match OPERATORS.may_load(
+        deps.storage,
+        (staker_raw.as_slice(), operator_raw.as_slice()),
+    )? {
+        Some(expires) if !expires.is_expired(&env.block) => Ok(staker_addr),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+pub fn approve_operator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let staker_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let operator_addr: Addr = deps.api.addr_validate(&operator)?;
+    let operator_raw: CanonicalAddr = deps.api.addr_canonicalize(operator_addr.as_str())?;
+    if staker_raw == operator_raw {
+        return Err(ContractError::CannotApproveSelf {});
+    }
+
+    let expires: Expiration = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::ApprovalExpired {});
+    }
+
+    OPERATORS.save(
+        deps.storage,
+        (staker_raw.as_slice(), operator_raw.as_slice()),
+        &expires,
+    )?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new(EVENT_APPROVE_OPERATOR)
+                .add_attribute(ATTR_STAKER, info.sender.to_string())
+                .add_attribute(ATTR_OPERATOR, operator_addr.to_string())
+                .add_attribute(ATTR_EXPIRES, expires.to_string()),
+        )
+        .add_attributes(vec![
+            attr("action", "approve_operator"),
+            attr("staker", info.sender),
+            attr("operator", operator_addr),
+            attr("expires", expires.to_string()),
+        ]))
+}
+
+pub fn revoke_operator(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response, ContractError> {
+    let staker_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let operator_addr: Addr = deps.api.addr_validate(&operator)?;
+    let operator_raw: CanonicalAddr = deps.api.addr_canonicalize(operator_addr.as_str())?;
+
+    OPERATORS.remove(
+        deps.storage,
+        (staker_raw.as_slice(), operator_raw.as_slice()),
+    );
+
+    Ok(Response::new()
+        .add_event(
+            Event::new(EVENT_REVOKE_OPERATOR)
+                .add_attribute(ATTR_STAKER, info.sender.to_string())
+                .add_attribute(ATTR_OPERATOR, operator_addr.to_string()),
+        )
+        .add_attributes(vec![
+            attr("action", "revoke_operator"),
+            attr("staker", info.sender),
+            attr("operator", operator_addr),
+        ]))
+}
+
+// the referrer must not be the staker nor be referred by the staker, directly or not
+fn validate_referrer(
+    deps: Deps,
+    staker_raw: &CanonicalAddr,
+    referrer_raw: &CanonicalAddr,
+) -> Result<(), ContractError> {
+    if staker_raw == referrer_raw {
+        return Err(ContractError::SelfReferral {});
+    }
+
+    let mut current_raw: CanonicalAddr = referrer_raw.clone();
+    while let Some(next_raw) = REFERRERS.may_load(deps.storage, current_raw.as_slice())? {
+        if next_raw == *staker_raw {
+            return Err(ContractError::ReferralCycle {});
+        }
+
+        current_raw = next_raw;
+    }
+
+    Ok(())
+}
+
+// the referral reward is moved from the claimed rewards of the referee, so the total owed
+// to the stakers does not change
+fn allocate_referral_reward(
+    storage: &mut dyn Storage,
+    state: &mut State,
+    referrer_raw: &CanonicalAddr,
+    amount: Uint256,
+) -> StdResult<()> {
+    let mut referrer_info: StakerInfo =
+        match staker_infos().may_load(storage, referrer_raw.as_slice())? {
+            Some(referrer_info) => referrer_info,
+            None => {
+                state.staker_count += 1;
+                StakerInfo::default()
+            }
+        };
+
+    compute_staker_reward(state, &mut referrer_info);
+    referrer_info.pending_reward += amount;
+    staker_infos().save(storage, referrer_raw.as_slice(), &referrer_info)?;
+
+    let earnings: Uint256 = REFERRAL_EARNINGS
+        .may_load(storage, referrer_raw.as_slice())?
+        .unwrap_or_default();
+    REFERRAL_EARNINGS.save(storage, referrer_raw.as_slice(), &(earnings + amount))?;
+
+    Ok(())
+}
+
+pub fn transfer_position(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Option<Uint256>,
+    include_rewards: Option<bool>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if config.receipt_token.is_some() {
+        return Err(ContractError::ReceiptTokenEnabled {});
+    }
+
+    let recipient_addr: Addr = deps.api.addr_validate(&recipient)?;
+    let (amount, reward_amount, response): (Uint256, Uint256, Response) = move_position(
+        deps,
+        &env,
+        &config,
+        &info.sender,
+        &recipient_addr,
+        amount,
+        include_rewards.unwrap_or(false),
+    )?;
+
+    Ok(response.add_attributes(vec![
+        attr("action", "transfer_position"),
+        attr("from", info.sender),
+        attr("to", recipient_addr),
+        attr("amount", amount.to_string()),
+        attr("reward_amount", reward_amount.to_string()),
+    ]))
+}
+
+pub fn receipt_transfer_hook(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from: String,
+    to: String,
+    amount: Uint256,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    match &config.receipt_token {
+        Some(receipt_token)
+            if *receipt_token == deps.api.addr_canonicalize(info.sender.as_str())? => {}
+        _ => return Err(ContractError::Unauthorized {}),
+    }
+
+    let from_addr: Addr = deps.api.addr_validate(&from)?;
+    let to_addr: Addr = deps.api.addr_validate(&to)?;
+    let (_, _, response): (Uint256, Uint256, Response) = move_position(
+        deps,
+        &env,
+        &config,
+        &from_addr,
+        &to_addr,
+        Some(amount),
+        false,
+    )?;
+
+    Ok(response.add_attributes(vec![
+        attr("action", "receipt_transfer"),
+        attr("from", from_addr),
+        attr("to", to_addr),
+        attr("amount", amount.to_string()),
+    ]))
+}
+
+// move the bond and optionally the pending rewards between two stakers,
+// returns the moved bond and reward amounts
+fn move_position(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    sender_addr: &Addr,
+    recipient_addr: &Addr,
+    amount: Option<Uint256>,
+    include_rewards: bool,
+) -> Result<(Uint256, Uint256, Response), ContractError> {
+    let sender_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(sender_addr.as_str())?;
+    let recipient_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(recipient_addr.as_str())?;
+    if sender_addr_raw == recipient_addr_raw {
+        return Err(ContractError::InvalidTransferRecipient {});
+    }
+
+    let mut state: State = STATE.load(deps.storage)?;
+    let mut staker_info: StakerInfo =
+        staker_infos().load(deps.storage, sender_addr_raw.as_slice())?;
+    let mut recipient_info: StakerInfo =
+        match staker_infos().may_load(deps.storage, recipient_addr_raw.as_slice())? {
+            Some(recipient_info) => recipient_info,
+            None => {
+                state.staker_count += 1;
+                StakerInfo::default()
+            }
+        };
+
+    let amount: Uint256 = match amount {
+        Some(amount) if amount.is_zero() || staker_info.bond_amount < amount => {
+            return Err(ContractError::InvalidTransferAmount {})
+        }
+        Some(amount) => amount,
+        None => staker_info.bond_amount,
+    };
+
+    // The recipient must be allowed to hold the position
+    if config.allowlist_enabled && !ALLOWLIST.has(deps.storage, recipient_addr_raw.as_slice()) {
+        return Err(ContractError::NotAllowlisted {});
+    }
+    if !config.max_bond_per_staker.is_zero()
+        && recipient_info.bond_amount
+            + read_owner_position_bond(deps.storage, &recipient_addr_raw)?
+            + amount
+            > config.max_bond_per_staker
+    {
+        return Err(ContractError::StakerBondCapExceeded {});
+    }
+
+    // Settle both parties before moving the bond
+    let global_reward_index: Decimal256 = state.global_reward_index;
+    compute_reward(config, &mut state, env.block.time.seconds());
+    compute_staker_reward(&state, &mut staker_info);
+    compute_staker_reward(&state, &mut recipient_info);
+
+    let sender_old_bond: Uint256 = staker_info.bond_amount;
+    let recipient_old_bond: Uint256 = recipient_info.bond_amount;
+    staker_info.bond_amount = staker_info.bond_amount - amount;
+    recipient_info.bond_amount += amount;
+
+    let reward_amount: Uint256 = if include_rewards {
+        staker_info.pending_reward
+    } else {
+        Uint256::zero()
+    };
+    staker_info.pending_reward = staker_info.pending_reward - reward_amount;
+    recipient_info.pending_reward += reward_amount;
+
+    update_staker_weight(
+        deps.as_ref(),
+        config,
+        &mut state,
+        sender_addr,
+        &mut staker_info,
+    )?;
+    update_staker_weight(
+        deps.as_ref(),
+        config,
+        &mut state,
+        recipient_addr,
+        &mut recipient_info,
+    )?;
+
+    // Store or remove the sender's staker_info
+    // depends on the left pending reward and bond amount
+    if staker_info.pending_reward.is_zero() && staker_info.bond_amount.is_zero() {
+        staker_infos().remove(deps.storage, sender_addr_raw.as_slice())?;
+        state.staker_count -= 1;
+        release_reward_dust(&mut state, &staker_info);
+    } else {
+        staker_infos().save(deps.storage, sender_addr_raw.as_slice(), &staker_info)?;
+    }
+    staker_infos().save(deps.storage, recipient_addr_raw.as_slice(), &recipient_info)?;
+    STATE.save(deps.storage, &state)?;
+
+    let sender_position_bond: Uint256 = read_owner_position_bond(deps.storage, &sender_addr_raw)?;
+    let recipient_position_bond: Uint256 =
+        read_owner_position_bond(deps.storage, &recipient_addr_raw)?;
+    let response: Response = Response::new()
+        .add_submessages(rewarder_messages(
+            deps.as_ref(),
+            config,
+            sender_addr,
+            staker_info.bond_amount + sender_position_bond,
+            staker_info.pending_reward,
+        )?)
+        .add_submessages(rewarder_messages(
+            deps.as_ref(),
+            config,
+            recipient_addr,
+            recipient_info.bond_amount + recipient_position_bond,
+            recipient_info.pending_reward,
+        )?)
+        .add_submessages(stake_changed_hook_messages(
+            deps.as_ref(),
+            sender_addr,
+            sender_old_bond + sender_position_bond,
+            staker_info.bond_amount + sender_position_bond,
+        )?)
+        .add_submessages(stake_changed_hook_messages(
+            deps.as_ref(),
+            recipient_addr,
+            recipient_old_bond + recipient_position_bond,
+            recipient_info.bond_amount + recipient_position_bond,
+        )?)
+        .add_events(vec![
+            distribute_event(deps.api, config, global_reward_index, &state)?,
+            staker_event(
+                EVENT_TRANSFER_POSITION_OUT,
+                sender_addr,
+                &staker_info,
+                &state,
+            )
+            .add_attribute(ATTR_COUNTERPARTY, recipient_addr.to_string())
+            .add_attribute(ATTR_AMOUNT, amount.to_string())
+            .add_attribute(ATTR_REWARD_AMOUNT, reward_amount.to_string()),
+            staker_event(
+                EVENT_TRANSFER_POSITION_IN,
+                recipient_addr,
+                &recipient_info,
+                &state,
+            )
+            .add_attribute(ATTR_COUNTERPARTY, sender_addr.to_string())
+            .add_attribute(ATTR_AMOUNT, amount.to_string())
+            .add_attribute(ATTR_REWARD_AMOUNT, reward_amount.to_string()),
+        ]);
+
+    Ok((amount, reward_amount, response))
+}
+
+pub fn bond_position(
+    deps: DepsMut,
+    env: Env,
+    sender_addr: Addr,
+    amount: Uint256,
+) -> Result<Response, ContractError> {
+    let sender_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(sender_addr.as_str())?;
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut state: State = STATE.load(deps.storage)?;
+    let staker_info: StakerInfo = staker_infos()
+        .may_load(deps.storage, sender_addr_raw.as_slice())?
+        .unwrap_or_default();
+
+    let (amount, deposit_fee): (Uint256, Uint256) = apply_bond_restrictions(
+        deps.as_ref(),
+        &env,
+        &config,
+        &state,
+        &sender_addr_raw,
+        &staker_info,
+        amount,
+    )?;
+
+    // Compute global reward, the position earns from the current index
+    let old_stake: Uint256 = read_owner_stake(deps.storage, &sender_addr_raw)?;
+    let global_reward_index: Decimal256 = state.global_reward_index;
+    compute_reward(&config, &mut state, env.block.time.seconds());
+
+    // Mint the position
+    state.last_position_id += 1;
+    state.position_count += 1;
+    let token_id: String = state.last_position_id.to_string();
+    let mut position: Position = Position {
+        owner: sender_addr_raw,
+        bond_time: env.block.time.seconds(),
+        staker_info: StakerInfo {
+            reward_index: state.global_reward_index,
+            ..StakerInfo::default()
+        },
+        approvals: vec![],
+    };
+    increase_bond_amount(&mut state, &mut position.staker_info, amount);
+    update_position_weight(&config, &mut state, &mut position);
+
+    positions().save(deps.storage, &token_id, &position)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_messages(deposit_fee_messages(deps.as_ref(), &config, deposit_fee)?)
+        .add_event(distribute_event(
+            deps.api,
+            &config,
+            global_reward_index,
+            &state,
+        )?)
+        .add_event(
+            staker_event(
+                EVENT_BOND_POSITION,
+                &sender_addr,
+                &position.staker_info,
+                &state,
+            )
+            .add_attribute(ATTR_TOKEN_ID, token_id.clone())
+            .add_attribute(ATTR_AMOUNT, amount.to_string())
+            .add_attribute(ATTR_DEPOSIT_FEE, deposit_fee.to_string()),
+        )
+        .add_submessages(rewarder_messages(
+            deps.as_ref(),
+            &config,
+            &sender_addr,
+            old_stake + amount,
+            position.staker_info.pending_reward,
+        )?)
+        .add_submessages(stake_changed_hook_messages(
+            deps.as_ref(),
+            &sender_addr,
+            old_stake,
+            old_stake + amount,
+        )?)
+        .add_attributes(vec![
+            attr("action", "mint"),
+            attr("minter", env.contract.address),
+            attr("owner", sender_addr),
+            attr("token_id", token_id),
+            attr("amount", amount.to_string()),
+            attr("deposit_fee", deposit_fee.to_string()),
+        ]))
+}
+
+pub fn unbond_position(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    amount: Option<Uint256>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut position: Position = positions().load(deps.storage, &token_id)?;
+    let owner_addr: Addr = resolve_position_owner(deps.as_ref(), &env, &info.sender, &position)?;
+
+    let amount_to_unbond: Uint256 = if let Some(amount) = amount {
+        if position.staker_info.bond_amount < amount {
+            return Err(ContractError::InvalidUnbondAmount {});
+        } else {
+            amount
+        }
+    } else {
+        position.staker_info.bond_amount
+    };
+
+    // Compute global reward & position reward
+    let old_stake: Uint256 = read_owner_stake(deps.storage, &position.owner)?;
+    let mut state: State = STATE.load(deps.storage)?;
+    let global_reward_index: Decimal256 = state.global_reward_index;
+    compute_reward(&config, &mut state, env.block.time.seconds());
+    compute_staker_reward(&state, &mut position.staker_info);
+
+    // Decrease bond_amount
+    decrease_bond_amount(&mut state, &mut position.staker_info, amount_to_unbond);
+    update_position_weight(&config, &mut state, &mut position);
+
+    let burned: bool = save_or_burn_position(deps.storage, &mut state, &token_id, &position)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_event(distribute_event(
+            deps.api,
+            &config,
+            global_reward_index,
+            &state,
+        )?)
+        .add_event(
+            staker_event(
+                EVENT_UNBOND_POSITION,
+                &owner_addr,
+                &position.staker_info,
+                &state,
+            )
+            .add_attribute(ATTR_TOKEN_ID, token_id.clone())
+            .add_attribute(ATTR_SENDER, info.sender.to_string())
+            .add_attribute(ATTR_AMOUNT, amount_to_unbond.to_string())
+            .add_attribute(ATTR_BURNED, burned.to_string()),
+        )
+        .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(&config.miaw_lp_token)?.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: owner_addr.to_string(),
+                amount: amount_to_unbond.into(),
+            })?,
+            funds: vec![],
+        })])
+        .add_submessages(rewarder_messages(
+            deps.as_ref(),
+            &config,
+            &owner_addr,
+            old_stake - amount_to_unbond,
+            position.staker_info.pending_reward,
+        )?)
+        .add_submessages(stake_changed_hook_messages(
+            deps.as_ref(),
+            &owner_addr,
+            old_stake,
+            old_stake - amount_to_unbond,
+        )?)
+        .add_attributes(vec![
+            attr("action", "unbond"),
+            attr("owner", owner_addr),
+            attr("token_id", token_id),
+            attr("amount", amount_to_unbond.to_string()),
+        ]))
+}
+
+pub fn claim_position_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut position: Position = positions().load(deps.storage, &token_id)?;
+    let owner_addr: Addr = resolve_position_owner(deps.as_ref(), &env, &info.sender, &position)?;
+
+    // Compute global reward & position reward
+    let mut state: State = STATE.load(deps.storage)?;
+    let global_reward_index: Decimal256 = state.global_reward_index;
+    compute_reward(&config, &mut state, env.block.time.seconds());
+    compute_staker_reward(&state, &mut position.staker_info);
+    update_position_weight(&config, &mut state, &mut position);
+
+    // The rewards of the position are paid to its owner
+    let pending_miaw: Uint256 = position.staker_info.pending_reward;
+    let (claim, burned): (Claim, bool) = settle_position_claim(
+        deps.storage,
+        &env,
+        &config,
+        &mut state,
+        &token_id,
+        &mut position,
+    )?;
+
+    STATE.save(deps.storage, &state)?;
+
+    let miaw_token: Addr = deps.api.addr_humanize(&config.miaw_token)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes = vec![
+        attr("action", "withdraw"),
+        attr("owner", owner_addr.to_string()),
+        attr("token_id", token_id.clone()),
+        attr("amount", claim.amount.to_string()),
+        attr("fee_amount", claim.fee_amount.to_string()),
+    ];
+    match claim.vesting_end_time {
+        Some(end_time) => attributes.push(attr("vesting_end_time", end_time.to_string())),
+        None => messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: miaw_token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: owner_addr.to_string(),
+                amount: claim.amount.into(),
+            })?,
+            funds: vec![],
+        })),
+    }
+    messages.extend(reward_fee_messages(
+        deps.as_ref(),
+        &config,
+        &miaw_token,
+        claim.fee_amount,
+    )?);
+
+    if let Some((referrer_raw, referral_amount)) = &claim.referral {
+        attributes.push(attr(
+            "referrer",
+            deps.api.addr_humanize(referrer_raw)?.to_string(),
+        ));
+        attributes.push(attr("referral_amount", referral_amount.to_string()));
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(rewarder_messages(
+            deps.as_ref(),
+            &config,
+            &owner_addr,
+            read_owner_stake(deps.storage, &position.owner)?,
+            pending_miaw,
+        )?)
+        .add_attributes(attributes)
+        .add_event(distribute_event(
+            deps.api,
+            &config,
+            global_reward_index,
+            &state,
+        )?)
+        .add_event(
+            claim_event(
+                deps.api,
+                EVENT_CLAIM_POSITION_REWARDS,
+                &owner_addr,
+                &position.staker_info,
+                &state,
+                &claim,
+            )?
+            .add_attribute(ATTR_TOKEN_ID, token_id)
+            .add_attribute(ATTR_SENDER, info.sender.to_string())
+            .add_attribute(ATTR_BURNED, burned.to_string()),
+        ))
+}
+
+pub fn transfer_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let recipient_addr: Addr = deps.api.addr_validate(&recipient)?;
+    let response: Response = move_nft(deps, &env, &info.sender, &recipient_addr, &token_id)?;
+
+    Ok(response.add_attributes(vec![
+        attr("action", "transfer_nft"),
+        attr("sender", info.sender),
+        attr("recipient", recipient_addr),
+        attr("token_id", token_id),
+    ]))
+}
+
+pub fn send_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    token_id: String,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let contract_addr: Addr = deps.api.addr_validate(&contract)?;
+    let response: Response = move_nft(deps, &env, &info.sender, &contract_addr, &token_id)?;
+
+    Ok(response
+        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw721ReceiverExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+                sender: info.sender.to_string(),
+                token_id: token_id.clone(),
+                msg,
+            }))?,
+            funds: vec![],
+        }))
+        .add_attributes(vec![
+            attr("action", "send_nft"),
+            attr("sender", info.sender),
+            attr("recipient", contract_addr),
+            attr("token_id", token_id),
+        ]))
+}
+
+// move a position along with its pending reward to the recipient
+fn move_nft(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    recipient_addr: &Addr,
+    token_id: &str,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut position: Position = positions().load(deps.storage, token_id)?;
+    check_can_send(deps.as_ref(), env, sender, &position)?;
+    let owner_addr: Addr = deps.api.addr_humanize(&position.owner)?;
+
+    let recipient_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(recipient_addr.as_str())?;
+    if recipient_addr_raw == position.owner {
+        return Err(ContractError::InvalidTransferRecipient {});
+    }
+
+    // The recipient must be allowed to hold the position
+    if config.allowlist_enabled && !ALLOWLIST.has(deps.storage, recipient_addr_raw.as_slice()) {
+        return Err(ContractError::NotAllowlisted {});
+    }
+    if !config.max_bond_per_staker.is_zero() {
+        let recipient_bond: Uint256 = staker_infos()
+            .may_load(deps.storage, recipient_addr_raw.as_slice())?
+            .unwrap_or_default()
+            .bond_amount
+            + read_owner_position_bond(deps.storage, &recipient_addr_raw)?;
+        if recipient_bond + position.staker_info.bond_amount > config.max_bond_per_staker {
+            return Err(ContractError::StakerBondCapExceeded {});
+        }
+    }
+
+    // Settle the position, its pending reward moves with it
+    let sender_old_stake: Uint256 = read_owner_stake(deps.storage, &position.owner)?;
+    let recipient_old_stake: Uint256 = read_owner_stake(deps.storage, &recipient_addr_raw)?;
+    let mut state: State = STATE.load(deps.storage)?;
+    let global_reward_index: Decimal256 = state.global_reward_index;
+    compute_reward(&config, &mut state, env.block.time.seconds());
+    compute_staker_reward(&state, &mut position.staker_info);
+    update_position_weight(&config, &mut state, &mut position);
+
+    position.owner = recipient_addr_raw;
+    position.approvals = vec![];
+    positions().save(deps.storage, token_id, &position)?;
+    STATE.save(deps.storage, &state)?;
+
+    let bond_amount: Uint256 = position.staker_info.bond_amount;
+    Ok(Response::new()
+        .add_submessages(rewarder_messages(
+            deps.as_ref(),
+            &config,
+            &owner_addr,
+            sender_old_stake - bond_amount,
+            Uint256::zero(),
+        )?)
+        .add_submessages(rewarder_messages(
+            deps.as_ref(),
+            &config,
+            recipient_addr,
+            recipient_old_stake + bond_amount,
+            position.staker_info.pending_reward,
+        )?)
+        .add_submessages(stake_changed_hook_messages(
+            deps.as_ref(),
+            &owner_addr,
+            sender_old_stake,
+            sender_old_stake - bond_amount,
+        )?)
+        .add_submessages(stake_changed_hook_messages(
+            deps.as_ref(),
+            recipient_addr,
+            recipient_old_stake,
+            recipient_old_stake + bond_amount,
+        )?)
+        .add_events(vec![
+            distribute_event(deps.api, &config, global_reward_index, &state)?,
+            staker_event(
+                EVENT_TRANSFER_NFT,
+                recipient_addr,
+                &position.staker_info,
+                &state,
+            )
+            .add_attribute(ATTR_TOKEN_ID, token_id)
+            .add_attribute(ATTR_SENDER, sender.to_string())
+            .add_attribute(ATTR_COUNTERPARTY, owner_addr.to_string()),
+        ]))
+}
+
+pub fn approve_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    token_id: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let mut position: Position = positions().load(deps.storage, &token_id)?;
+    check_can_approve(deps.as_ref(), &env, &info.sender, &position)?;
+
+    let spender_addr: Addr = deps.api.addr_validate(&spender)?;
+    let spender_raw: CanonicalAddr = deps.api.addr_canonicalize(spender_addr.as_str())?;
+    if spender_raw == position.owner {
+        return Err(ContractError::CannotApproveSelf {});
+    }
+
+    let expires: Expiration = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::ApprovalExpired {});
+    }
+
+    position
+        .approvals
+        .retain(|approval| approval.spender != spender_raw);
+    position.approvals.push(PositionApproval {
+        spender: spender_raw,
+        expires,
+    });
+    positions().save(deps.storage, &token_id, &position)?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new(EVENT_APPROVE)
+                .add_attribute(ATTR_TOKEN_ID, token_id.clone())
+                .add_attribute(ATTR_SENDER, info.sender.to_string())
+                .add_attribute(ATTR_SPENDER, spender_addr.to_string())
+                .add_attribute(ATTR_EXPIRES, expires.to_string()),
+        )
+        .add_attributes(vec![
+            attr("action", "approve"),
+            attr("sender", info.sender),
+            attr("spender", spender_addr),
+            attr("token_id", token_id),
+        ]))
+}
+
+pub fn revoke_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let mut position: Position = positions().load(deps.storage, &token_id)?;
+    check_can_approve(deps.as_ref(), &env, &info.sender, &position)?;
+
+    let spender_addr: Addr = deps.api.addr_validate(&spender)?;
+    let spender_raw: CanonicalAddr = deps.api.addr_canonicalize(spender_addr.as_str())?;
+    position
+        .approvals
+        .retain(|approval| approval.spender != spender_raw);
+    positions().save(deps.storage, &token_id, &position)?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new(EVENT_REVOKE)
+                .add_attribute(ATTR_TOKEN_ID, token_id.clone())
+                .add_attribute(ATTR_SENDER, info.sender.to_string())
+                .add_attribute(ATTR_SPENDER, spender_addr.to_string()),
+        )
+        .add_attributes(vec![
+            attr("action", "revoke"),
+            attr("sender", info.sender),
+            attr("spender", spender_addr),
+            attr("token_id", token_id),
+        ]))
+}
+
+pub fn approve_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let owner_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let operator_addr: Addr = deps.api.addr_validate(&operator)?;
+    let operator_raw: CanonicalAddr = deps.api.addr_canonicalize(operator_addr.as_str())?;
+    if owner_raw == operator_raw {
+        return Err(ContractError::CannotApproveSelf {});
+    }
+
+    let expires: Expiration = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::ApprovalExpired {});
+    }
+
+    NFT_OPERATORS.save(
+        deps.storage,
+        (owner_raw.as_slice(), operator_raw.as_slice()),
+        &expires,
+    )?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new(EVENT_APPROVE_ALL)
+                .add_attribute(ATTR_SENDER, info.sender.to_string())
+                .add_attribute(ATTR_OPERATOR, operator_addr.to_string())
+                .add_attribute(ATTR_EXPIRES, expires.to_string()),
+        )
+        .add_attributes(vec![
+            attr("action", "approve_all"),
+            attr("sender", info.sender),
+            attr("operator", operator_addr),
+        ]))
+}
+
+pub fn revoke_all(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response, ContractError> {
+    let owner_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let operator_addr: Addr = deps.api.addr_validate(&operator)?;
+    let operator_raw: CanonicalAddr = deps.api.addr_canonicalize(operator_addr.as_str())?;
+
+    NFT_OPERATORS.remove(
+        deps.storage,
+        (owner_raw.as_slice(), operator_raw.as_slice()),
+    );
+
+    Ok(Response::new()
+        .add_event(
+            Event::new(EVENT_REVOKE_ALL)
+                .add_attribute(ATTR_SENDER, info.sender.to_string())
+                .add_attribute(ATTR_OPERATOR, operator_addr.to_string()),
+        )
+        .add_attributes(vec![
+            attr("action", "revoke_all"),
+            attr("sender", info.sender),
+            attr("operator", operator_addr),
+        ]))
+}
+
+// the cw721 approvals of a position are managed by its owner or an operator
+// approved for all of its positions
+fn check_can_approve(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    position: &Position,
+) -> Result<(), ContractError> {
+    let sender_raw: CanonicalAddr = deps.api.addr_canonicalize(sender.as_str())?;
+    if sender_raw == position.owner {
+        return Ok(());
+    }
+
+    match NFT_OPERATORS.may_load(
+        deps.storage,
+        (position.owner.as_slice(), sender_raw.as_slice()),
+    )? {
+        Some(expires) if !expires.is_expired(&env.block) => Ok(()),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+// a position is also transferred by the spenders approved for it, the staking
+// operators can only unbond and claim for the owner
+fn check_can_send(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    position: &Position,
+) -> Result<(), ContractError> {
+    let sender_raw: CanonicalAddr = deps.api.addr_canonicalize(sender.as_str())?;
+    if position
+        .approvals
+        .iter()
+        .any(|approval| approval.spender == sender_raw && !approval.expires.is_expired(&env.block))
+    {
+        return Ok(());
+    }
+
+    check_can_approve(deps, env, sender, position)
+}
+
+// the owner of the position, when the sender is the owner or an operator approved by the owner
+fn resolve_position_owner(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    position: &Position,
+) -> Result<Addr, ContractError> {
+    let owner_addr: Addr = deps.api.addr_humanize(&position.owner)?;
+    resolve_staker(deps, env, sender, Some(owner_addr.to_string()))
+}
+
+// store the position, or burn it once it holds neither bond nor pending reward,
+// returns whether it was burned
+fn save_or_burn_position(
+    storage: &mut dyn Storage,
+    state: &mut State,
+    token_id: &str,
+    position: &Position,
+) -> StdResult<bool> {
+    if position.staker_info.pending_reward.is_zero() && position.staker_info.bond_amount.is_zero() {
+        positions().remove(storage, token_id)?;
+        state.position_count -= 1;
+        release_reward_dust(state, &position.staker_info);
+        Ok(true)
+    } else {
+        positions().save(storage, token_id, position)?;
+        Ok(false)
+    }
+}
+
+// positions are not boosted, they earn the base weight of their bond when a boost is set
+fn update_position_weight(config: &Config, state: &mut State, position: &mut Position) {
+    let weight: Uint256 = if config.boost_contract.is_some() {
+        position.staker_info.bond_amount * Decimal256::percent(BOOST_BASE_WEIGHT)
+    } else {
+        position.staker_info.bond_amount
+    };
+    set_staker_weight(state, &mut position.staker_info, weight);
+}
+
+pub fn kick(deps: DepsMut, env: Env, staker: String) -> Result<Response, ContractError> {
+    let staker_addr: Addr = deps.api.addr_validate(&staker)?;
+    let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut state: State = STATE.load(deps.storage)?;
+    let mut staker_info: StakerInfo =
+        staker_infos().load(deps.storage, staker_addr_raw.as_slice())?;
+
+    // Compute global reward & staker reward with the weight before the decay
+    let global_reward_index: Decimal256 = state.global_reward_index;
+    compute_reward(&config, &mut state, env.block.time.seconds());
+    compute_staker_reward(&state, &mut staker_info);
+
+    let weight: Uint256 = compute_staker_weight(
+        deps.as_ref(),
+        &config,
+        &state,
+        &staker_addr,
+        staker_info.bond_amount,
+    )?;
+    if weight >= staker_info.weight {
+        return Err(ContractError::BoostNotDecayed {});
+    }
+    set_staker_weight(&mut state, &mut staker_info, weight);
+
+    staker_infos().save(deps.storage, staker_addr_raw.as_slice(), &staker_info)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_event(distribute_event(
+            deps.api,
+            &config,
+            global_reward_index,
+            &state,
+        )?)
+        .add_event(staker_event(EVENT_KICK, &staker_addr, &staker_info, &state))
+        .add_attributes(vec![
+            attr("action", "kick"),
+            attr("staker", staker_addr),
+            attr("weight", weight.to_string()),
+        ]))
+}
+
+pub fn withdraw_vested(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let sender_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let current_time: u64 = env.block.time.seconds();
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut state: State = STATE.load(deps.storage)?;
+    let mut schedules: Vec<VestingSchedule> = VESTING_SCHEDULES
+        .may_load(deps.storage, sender_addr_raw.as_slice())?
+        .unwrap_or_default();
+
+    let mut amount: Uint256 = Uint256::zero();
+    for schedule in schedules.iter_mut() {
+        let claimable_amount: Uint256 = schedule.claimable_amount(current_time);
+        schedule.withdrawn_amount += claimable_amount;
+        amount += claimable_amount;
+    }
+    if amount.is_zero() {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+
+    // Remove the fully withdrawn schedules
+    schedules.retain(|schedule| schedule.withdrawn_amount < schedule.amount);
+    if schedules.is_empty() {
+        VESTING_SCHEDULES.remove(deps.storage, sender_addr_raw.as_slice());
+    } else {
+        VESTING_SCHEDULES.save(deps.storage, sender_addr_raw.as_slice(), &schedules)?;
+    }
+
+    state.vesting_amount = state.vesting_amount - amount;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(&config.miaw_token)?.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: info.sender.to_string(),
+                amount: amount.into(),
+            })?,
+            funds: vec![],
+        })])
+        .add_event(
+            Event::new(EVENT_WITHDRAW_VESTED)
+                .add_attribute(ATTR_STAKER, info.sender.to_string())
+                .add_attribute(ATTR_AMOUNT, amount.to_string()),
+        )
+        .add_attributes(vec![
+            attr("action", "withdraw_vested"),
+            attr("owner", info.sender),
+            attr("amount", amount.to_string()),
+        ]))
+}
+
+pub fn withdraw_vested_early(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let sender_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let current_time: u64 = env.block.time.seconds();
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    if config.early_withdraw_penalty.is_zero() {
+        return Err(ContractError::EarlyWithdrawDisabled {});
+    }
+
+    let mut state: State = STATE.load(deps.storage)?;
+    let schedules: Vec<VestingSchedule> = VESTING_SCHEDULES
+        .may_load(deps.storage, sender_addr_raw.as_slice())?
+        .unwrap_or_default();
+
+    let mut claimable_amount: Uint256 = Uint256::zero();
+    let mut locked_amount: Uint256 = Uint256::zero();
+    for schedule in schedules.iter() {
+        let schedule_claimable_amount: Uint256 = schedule.claimable_amount(current_time);
+        claimable_amount += schedule_claimable_amount;
+        locked_amount += schedule.amount - schedule.withdrawn_amount - schedule_claimable_amount;
+    }
+    if claimable_amount.is_zero() && locked_amount.is_zero() {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+
+    VESTING_SCHEDULES.remove(deps.storage, sender_addr_raw.as_slice());
+    state.vesting_amount = state.vesting_amount - claimable_amount - locked_amount;
+
+    // The penalty goes back to the stakers through the global reward index
+    let global_reward_index: Decimal256 = state.global_reward_index;
+    compute_reward(&config, &mut state, current_time);
+    let penalty_amount: Uint256 = locked_amount * config.early_withdraw_penalty;
+    distribute_reward(&config, &mut state, penalty_amount);
+    STATE.save(deps.storage, &state)?;
+
+    let amount: Uint256 = claimable_amount + locked_amount - penalty_amount;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !amount.is_zero() {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(&config.miaw_token)?.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: info.sender.to_string(),
+                amount: amount.into(),
+            })?,
+            funds: vec![],
+        }));
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_event(distribute_event(
+            deps.api,
+            &config,
+            global_reward_index,
+            &state,
+        )?)
+        .add_event(
+            Event::new(EVENT_WITHDRAW_VESTED_EARLY)
+                .add_attribute(ATTR_STAKER, info.sender.to_string())
+                .add_attribute(ATTR_AMOUNT, amount.to_string())
+                .add_attribute(ATTR_PENALTY_AMOUNT, penalty_amount.to_string()),
+        )
+        .add_attributes(vec![
+            attr("action", "withdraw_vested_early"),
+            attr("owner", info.sender),
+            attr("amount", amount.to_string()),
+            attr("penalty_amount", penalty_amount.to_string()),
+        ]))
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: UpdateConfigMsg,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Settle the rewards emitted under the current config
+    let mut state: State = STATE.load(deps.storage)?;
+    let global_reward_index: Decimal256 = state.global_reward_index;
+    compute_reward(&config, &mut state, env.block.time.seconds());
+    STATE.save(deps.storage, &state)?;
+
+    let mut updated: Vec<&str> = vec![];
+    if let Some(owner) = msg.owner {
+        config.owner = deps.api.addr_canonicalize(&owner)?;
+        updated.push("owner");
+    }
+
+    if let Some(page_limits) = msg.page_limits {
+        if page_limits.default_limit == 0 || page_limits.default_limit > page_limits.max_limit {
+            return Err(ContractError::InvalidPageLimits {});
+        }
+
+        config.max_limit = page_limits.max_limit;
+        config.default_limit = page_limits.default_limit;
+        updated.push("page_limits");
+    }
+
+    if let Some(undistributed_policy) = msg.undistributed_policy {
+        config.undistributed_policy = undistributed_policy;
+        updated.push("undistributed_policy");
+    }
+
+    if let Some(vesting) = msg.vesting {
+        if vesting.early_withdraw_penalty > Decimal256::one() {
+            return Err(ContractError::InvalidPenaltyRate {});
+        }
+
+        config.vesting_duration = vesting.duration;
+        config.early_withdraw_penalty = vesting.early_withdraw_penalty;
+        updated.push("vesting");
+    }
+
+    if let Some(boost) = msg.boost {
+        config.boost_contract = match boost.contract {
+            Some(boost_contract) => Some(deps.api.addr_canonicalize(&boost_contract)?),
+            None => None,
+        };
+        config.boost_source = boost.source;
+        updated.push("boost");
+    }
+
+    if let Some(reward_fees) = msg.reward_fees {
+        if reward_fees.referral_rate > Decimal256::percent(MAX_REFERRAL_RATE) {
+            return Err(ContractError::InvalidReferralRate {});
+        }
+
+        if reward_fees.reward_fee_rate > Decimal256::percent(MAX_REWARD_FEE_RATE) {
+            return Err(ContractError::InvalidRewardFeeRate {});
+        }
+
+        if !reward_fees.reward_fee_rate.is_zero() && reward_fees.fee_collector.is_none() {
+            return Err(ContractError::FeeCollectorNotSet {});
+        }
+
+        config.referral_rate = reward_fees.referral_rate;
+        config.reward_fee_rate = reward_fees.reward_fee_rate;
+        config.fee_collector = match reward_fees.fee_collector {
+            Some(fee_collector) => Some(deps.api.addr_canonicalize(&fee_collector)?),
+            None => None,
+        };
+        updated.push("reward_fees");
+    }
+
+    if let Some(bond_restrictions) = msg.bond_restrictions {
+        if bond_restrictions.deposit_fee_rate > Decimal256::percent(MAX_DEPOSIT_FEE_RATE) {
+            return Err(ContractError::InvalidDepositFeeRate {});
+        }
+
+        config.allowlist_enabled = bond_restrictions.allowlist_enabled;
+        config.max_bond_per_staker = bond_restrictions.max_bond_per_staker;
+        config.max_total_bond = bond_restrictions.max_total_bond;
+        config.deposit_fee_rate = bond_restrictions.deposit_fee_rate;
+        config.treasury = match bond_restrictions.treasury {
+            Some(treasury) => Some(deps.api.addr_canonicalize(&treasury)?),
+            None => None,
+        };
+        updated.push("bond_restrictions");
+    }
+
+    if !config.deposit_fee_rate.is_zero() && config.treasury.is_none() {
+        return Err(ContractError::TreasuryNotSet {});
+    }
+
+    if let Some(farm_window) = msg.farm_window {
+        if let (Some(start_time), Some(end_time)) = (farm_window.start_time, farm_window.end_time) {
+            if start_time >= end_time {
+                return Err(ContractError::InvalidFarmWindow {});
+            }
+        }
+
+        config.start_time = farm_window.start_time;
+        config.end_time = farm_window.end_time;
+        updated.push("farm_window");
+    }
+
+    if let Some(paused) = msg.paused {
+        config.paused = paused;
+        updated.push("paused");
+    }
+
+    if let Some(reward_deposit_duration) = msg.reward_deposit_duration {
+        config.reward_deposit_duration = reward_deposit_duration;
+        updated.push("reward_deposit_duration");
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_event(distribute_event(
+            deps.api,
+            &config,
+            global_reward_index,
+            &state,
+        )?)
+        .add_event(Event::new(EVENT_UPDATE_CONFIG).add_attribute(ATTR_UPDATED, updated.join(",")))
+        .add_attributes(vec![attr("action", "update_config")]))
+}
+
+pub fn recover_undistributed(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if config.undistributed_policy != UndistributedPolicy::Reclaim {
+        return Err(ContractError::UndistributedNotReclaimable {});
+    }
+
+    let recipient: Addr = deps.api.addr_validate(&recipient)?;
+
+    // Compute global reward to account the rewards emitted until now
+    let mut state: State = STATE.load(deps.storage)?;
+    let global_reward_index: Decimal256 = state.global_reward_index;
+    compute_reward(&config, &mut state, env.block.time.seconds());
+
+    let amount: Uint256 = state.undistributed_amount;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToRecover {});
+    }
+    state.undistributed_amount = Uint256::zero();
+
+    // Store updated state
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_event(distribute_event(
+            deps.api,
+            &config,
+            global_reward_index,
+            &state,
+        )?)
+        .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(&config.miaw_token)?.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: amount.into(),
+            })?,
+            funds: vec![],
+        })])
+        .add_event(
+            Event::new(EVENT_RECOVER_UNDISTRIBUTED)
+                .add_attribute(ATTR_RECIPIENT, recipient.to_string())
+                .add_attribute(ATTR_AMOUNT, amount.to_string()),
+        )
+        .add_attributes(vec![
+            attr("action", "recover_undistributed"),
+            attr("recipient", recipient),
+            attr("amount", amount.to_string()),
+        ]))
+}
+
+pub fn sweep_tokens(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset: AssetInfo,
+    recipient: String,
+    amount: Option<Uint256>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let recipient: Addr = deps.api.addr_validate(&recipient)?;
+
+    // Compute global reward, so the rewards owed to stakers are up to date
+    let mut state: State = STATE.load(deps.storage)?;
+    compute_reward(&config, &mut state, env.block.time.seconds());
+
+    let sweepable_amount: Uint256 = match &asset {
+        AssetInfo::Token { contract_addr } => {
+            let token_raw: CanonicalAddr = deps.api.addr_canonicalize(contract_addr)?;
+            if token_raw == config.miaw_lp_token {
+                query_lp_surplus(deps.as_ref(), &env, &config, &state)?
+            } else {
+                let balance: Uint256 = query_token_balance(
+                    &deps.querier,
+                    deps.api.addr_validate(contract_addr)?,
+                    env.contract.address.clone(),
+                )?
+                .into();
+
+                if token_raw == config.miaw_token {
+                    let reserved_amount: Uint256 = reserved_reward_amount(&config, &state);
+                    if balance > reserved_amount {
+                        balance - reserved_amount
+                    } else {
+                        Uint256::zero()
+                    }
+                } else {
+                    balance
+                }
+            }
+        }
+        AssetInfo::NativeToken { denom } => {
+            query_balance(&deps.querier, env.contract.address.clone(), denom.clone())?.into()
+        }
+    };
+
+    let amount: Uint256 = match amount {
+        Some(amount) if amount > sweepable_amount => {
+            return Err(ContractError::SweepAmountExceedsSurplus {})
+        }
+        Some(amount) => amount,
+        None => sweepable_amount,
+    };
+    if amount.is_zero() {
+        return Err(ContractError::NothingToRecover {});
+    }
+
+    let message: CosmosMsg = match &asset {
+        AssetInfo::Token { contract_addr } => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.clone(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: amount.into(),
+            })?,
+            funds: vec![],
+        }),
+        AssetInfo::NativeToken { denom } => CosmosMsg::Bank(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin {
+                denom: denom.clone(),
+                amount: amount.into(),
+            }],
+        }),
+    };
+
+    Ok(Response::new()
+        .add_message(message)
+        .add_event(
+            Event::new(EVENT_SWEEP_TOKENS)
+                .add_attribute(
+                    ATTR_ASSET,
+                    match &asset {
+                        AssetInfo::Token { contract_addr } => contract_addr.clone(),
+                        AssetInfo::NativeToken { denom } => denom.clone(),
+                    },
+                )
+                .add_attribute(ATTR_RECIPIENT, recipient.to_string())
+                .add_attribute(ATTR_AMOUNT, amount.to_string()),
+        )
+        .add_attributes(vec![
+            attr("action", "sweep_tokens"),
+            attr("recipient", recipient),
+            attr("amount", amount.to_string()),
+        ]))
+}
+
+// LP tokens held by the contract on top of the bonded amount
+fn query_lp_surplus(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    state: &State,
+) -> Result<Uint256, ContractError> {
+    let lp_token: Addr = deps.api.addr_humanize(&config.miaw_lp_token)?;
+    let lp_token_balance: Uint256 =
+        query_token_balance(&deps.querier, lp_token, env.contract.address.clone())?.into();
+
+    Ok(lp_token_balance - state.total_bond_amount)
+}
+
+// reward tokens distributed but not claimed yet, still vesting, put aside while nothing
+// was bonded and still to be emitted by the distribution schedule
+fn reserved_reward_amount(config: &Config, state: &State) -> Uint256 {
+    // the counters may lag behind the stakers of a migrated contract
+    let owed_amount: Uint256 =
+        if state.cumulative_reward_distributed > state.cumulative_reward_claimed {
+            state.cumulative_reward_distributed - state.cumulative_reward_claimed
+        } else {
+            Uint256::zero()
+        };
+    let mut reserved_amount: Uint256 = owed_amount
+        + state.vesting_amount
+        + state.undistributed_amount
+        + state.reward_stream_amount
+        + state.referral_reserve;
+    for s in config.distribution_schedule.iter() {
+        let time: u64 = state.last_distributed.max(s.0).min(s.1);
+        reserved_amount += s.2 - emitted_amount(s, time);
+    }
+
+    reserved_amount
+}
+
+pub fn increase_bond_amount(state: &mut State, staker_info: &mut StakerInfo, amount: Uint256) {
+    state.total_bond_amount += amount;
+    state.cumulative_bond_amount += amount;
+    staker_info.bond_amount += amount;
+}
+
+pub fn decrease_bond_amount(state: &mut State, staker_info: &mut StakerInfo, amount: Uint256) {
+    state.total_bond_amount = state.total_bond_amount - amount;
+    state.cumulative_unbond_amount += amount;
+    staker_info.bond_amount = staker_info.bond_amount - amount;
+}
+
+// re-checkpoint the weight of the staker from its bond amount and current boost
+pub fn update_staker_weight(
+    deps: Deps,
+    config: &Config,
+    state: &mut State,
+    staker_addr: &Addr,
+    staker_info: &mut StakerInfo,
+) -> StdResult<()> {
+    let weight: Uint256 =
+        compute_staker_weight(deps, config, state, staker_addr, staker_info.bond_amount)?;
+    set_staker_weight(state, staker_info, weight);
+
+    Ok(())
+}
+
+// min(bond, 0.4 * bond + 0.6 * total_bond * boost_balance / boost_supply)
+fn compute_staker_weight(
+    deps: Deps,
+    config: &Config,
+    state: &State,
+    staker_addr: &Addr,
+    bond_amount: Uint256,
+) -> StdResult<Uint256> {
+    let boost_contract: Addr = match &config.boost_contract {
+        Some(boost_contract) => deps.api.addr_humanize(boost_contract)?,
+        None => return Ok(bond_amount),
+    };
+
+    let mut weight: Uint256 = bond_amount * Decimal256::percent(BOOST_BASE_WEIGHT);
+    let boost_supply: Uint256 = match config.boost_source {
+        BoostSource::Cw20 => {
+            let res: TokenInfoResponse = deps
+                .querier
+                .query_wasm_smart(boost_contract.to_string(), &Cw20QueryMsg::TokenInfo {})?;
+            res.total_supply.into()
+        }
+        BoostSource::Staking => {
+            let res: TotalStakedResponse = deps
+                .querier
+                .query_wasm_smart(boost_contract.to_string(), &BoostQueryMsg::TotalStaked {})?;
+            res.total
+        }
+    };
+    if !boost_supply.is_zero() {
+        let boost_balance: Uint256 = match config.boost_source {
+            BoostSource::Cw20 => {
+                query_token_balance(&deps.querier, boost_contract, staker_addr.clone())?.into()
+            }
+            BoostSource::Staking => {
+                let res: StakedBalanceResponse = deps.querier.query_wasm_smart(
+                    boost_contract.to_string(),
+                    &BoostQueryMsg::StakedBalance {
+                        address: staker_addr.to_string(),
+                    },
+                )?;
+                res.balance
+            }
+        };
+        weight += state
+            .total_bond_amount
+            .multiply_ratio(boost_balance.0, boost_supply.0)
+            * Decimal256::percent(100 - BOOST_BASE_WEIGHT);
+    }
+
+    Ok(std::cmp::min(weight, bond_amount))
+}
+
+fn set_staker_weight(state: &mut State, staker_info: &mut StakerInfo, weight: Uint256) {
+    state.total_weight = state.total_weight - staker_info.weight + weight;
+    staker_info.weight = weight;
+}
+
+// compute distributed rewards and update global reward index
+pub fn compute_reward(config: &Config, state: &mut State, current_time: u64) {
     let mut distributed_amount: Uint256 = Uint256::zero();
     for s in config.distribution_schedule.iter() {
         if s.0 > current_time || s.1 < state.last_distributed {
             continue;
         }
 
-        // min(s.1, current_time) - max(s.0, last_distributed)
-        let seconds_passed =
-            std::cmp::min(s.1, current_time) - std::cmp::max(s.0, state.last_distributed);
+        // emitted(min(s.1, current_time)) - emitted(max(s.0, last_distributed)),
+        // the rounding of a curve integral may dip by a unit
+        let emitted: Uint256 = emitted_amount(s, std::cmp::min(s.1, current_time));
+        let emitted_before: Uint256 = emitted_amount(s, std::cmp::max(s.0, state.last_distributed));
+        if emitted > emitted_before {
+            distributed_amount += emitted - emitted_before;
+        }
+    }
 
-        let num_seconds = s.1 - s.0;
-        let distribution_amount_per_second: Decimal256 = Decimal256::from_ratio(s.2, num_seconds);
-        distributed_amount += distribution_amount_per_second * Uint256::from(seconds_passed);
+    // Stream the deposited rewards at the reward rate, the rounding dust
+    // is released with the rest at the end time
+    if !state.reward_stream_amount.is_zero() && current_time > state.last_distributed {
+        let streamed_amount: Uint256 = if current_time >= state.reward_stream_end_time {
+            state.reward_rate = Decimal256::zero();
+            state.reward_stream_amount
+        } else {
+            std::cmp::min(
+                Uint256::from(current_time - state.last_distributed) * state.reward_rate,
+                state.reward_stream_amount,
+            )
+        };
+        state.reward_stream_amount = state.reward_stream_amount - streamed_amount;
+        distributed_amount += streamed_amount;
     }
 
     state.last_distributed = current_time;
-    state.global_reward_index +=
-        Decimal256::from_ratio(distributed_amount, state.total_bond_amount);
+
+    distribute_reward(config, state, distributed_amount);
+}
+
+// add the reward amount to the global reward index
+fn distribute_reward(config: &Config, state: &mut State, mut distributed_amount: Uint256) {
+    // nobody can receive the rewards, keep them aside according to the policy
+    if state.total_weight.is_zero() {
+        state.undistributed_amount += distributed_amount;
+        return;
+    }
+
+    if config.undistributed_policy == UndistributedPolicy::CarryForward {
+        distributed_amount += state.undistributed_amount;
+        state.undistributed_amount = Uint256::zero();
+    }
+
+    state.cumulative_reward_distributed += distributed_amount;
+
+    // the part the index cannot represent is carried over to the next update
+    let distributable_amount: Decimal256 =
+        Decimal256::from_uint256(distributed_amount) + state.reward_remainder;
+    let total_weight: Decimal256 = Decimal256::from_uint256(state.total_weight);
+    let reward_per_weight: Decimal256 = distributable_amount / total_weight;
+    state.global_reward_index += reward_per_weight;
+    state.reward_remainder = distributable_amount - total_weight * reward_per_weight;
+}
+
+// amount emitted by the schedule entry from its start until the given time,
+// computed from the start so that rounding does not accumulate across updates
+pub fn emitted_amount(s: &ScheduleEntry, time: u64) -> Uint256 {
+    let time: u64 = time.max(s.0).min(s.1);
+    if s.3 == EmissionCurve::Linear {
+        return s.2.multiply_ratio(time - s.0, s.1 - s.0);
+    }
+
+    let emitted: Decimal256 = Decimal256::from_uint256(s.2) * curve_integral(&s.3, time - s.0)
+        / curve_integral(&s.3, s.1 - s.0);
+    std::cmp::min(Uint256::one() * emitted, s.2)
+}
+
+// reward tokens emitted per second by the schedule entry at the given time
+pub fn emission_rate(s: &ScheduleEntry, time: u64) -> Decimal256 {
+    if time < s.0 || time >= s.1 {
+        return Decimal256::zero();
+    }
+
+    Decimal256::from_uint256(s.2) * curve_rate(&s.3, time - s.0) / curve_integral(&s.3, s.1 - s.0)
+}
+
+pub fn validate_distribution_schedule(schedule: &[ScheduleEntry]) -> Result<(), ContractError> {
+    for s in schedule.iter() {
+        if s.0 >= s.1 {
+            return Err(ContractError::InvalidDistributionSchedule {});
+        }
+
+        let valid: bool = match &s.3 {
+            EmissionCurve::Linear => true,
+            EmissionCurve::Halving { period } => *period > 0,
+            EmissionCurve::SteppedDecay { step, decay } => *step > 0 && *decay <= Decimal256::one(),
+            EmissionCurve::PiecewiseLinear { points } => {
+                points.len() >= 2
+                    && points[0].0 == 0
+                    && points[points.len() - 1].0 == s.1 - s.0
+                    && points.windows(2).all(|p| p[0].0 < p[1].0)
+                    && !curve_integral(&s.3, s.1 - s.0).is_zero()
+            }
+        };
+        if !valid {
+            return Err(ContractError::InvalidDistributionSchedule {});
+        }
+    }
+
+    Ok(())
+}
+
+// relative emission rate of the curve `x` seconds after the start of the entry
+fn curve_rate(curve: &EmissionCurve, x: u64) -> Decimal256 {
+    match curve {
+        EmissionCurve::Linear => Decimal256::one(),
+        EmissionCurve::Halving { period } => decimal_pow(Decimal256::percent(50), x / period),
+        EmissionCurve::SteppedDecay { step, decay } => decimal_pow(*decay, x / step),
+        EmissionCurve::PiecewiseLinear { points } => match points.iter().position(|p| p.0 > x) {
+            Some(i) => interpolate(points[i - 1], points[i], x),
+            None => points[points.len() - 1].1,
+        },
+    }
+}
+
+// integral of the relative emission rate over the first `x` seconds of the entry
+fn curve_integral(curve: &EmissionCurve, x: u64) -> Decimal256 {
+    match curve {
+        EmissionCurve::Linear => Decimal256::from_uint256(x),
+        EmissionCurve::Halving { period } => stepped_integral(Decimal256::percent(50), *period, x),
+        EmissionCurve::SteppedDecay { step, decay } => stepped_integral(*decay, *step, x),
+        EmissionCurve::PiecewiseLinear { points } => {
+            let mut integral: Decimal256 = Decimal256::zero();
+            for p in points.windows(2) {
+                if x <= p[0].0 {
+                    break;
+                }
+
+                // trapezoid up to the end of the segment or x
+                let end: u64 = std::cmp::min(x, p[1].0);
+                integral += (p[0].1 + interpolate(p[0], p[1], end))
+                    * Decimal256::from_uint256(end - p[0].0)
+                    / Decimal256::from_uint256(2u64);
+            }
+
+            integral
+        }
+    }
+}
+
+// n full steps at the rates 1, decay, .., decay^(n-1) and the current step at decay^n:
+// step * (1 - decay^n) / (1 - decay) + decay^n * (x - n * step)
+fn stepped_integral(decay: Decimal256, step: u64, x: u64) -> Decimal256 {
+    let n: u64 = x / step;
+    let decay_n: Decimal256 = decimal_pow(decay, n);
+    let full_steps: Decimal256 = if decay == Decimal256::one() {
+        Decimal256::from_uint256(n)
+    } else {
+        (Decimal256::one() - decay_n) / (Decimal256::one() - decay)
+    };
+
+    full_steps * Decimal256::from_uint256(step) + decay_n * Decimal256::from_uint256(x - n * step)
+}
+
+fn interpolate(p0: (u64, Decimal256), p1: (u64, Decimal256), x: u64) -> Decimal256 {
+    (p0.1 * Decimal256::from_uint256(p1.0 - x) + p1.1 * Decimal256::from_uint256(x - p0.0))
+        / Decimal256::from_uint256(p1.0 - p0.0)
+}
+
+fn decimal_pow(mut base: Decimal256, mut exp: u64) -> Decimal256 {
+    let mut result: Decimal256 = Decimal256::one();
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base;
+        }
+        base = base * base;
+        exp >>= 1;
+    }
+
+    result
 }
 
 // withdraw reward to pending reward
 pub fn compute_staker_reward(state: &State, staker_info: &mut StakerInfo) {
-    let pending_reward: Uint256 = (staker_info.bond_amount * state.global_reward_index)
-        - (staker_info.bond_amount * staker_info.reward_index);
+    // the fraction of a unit left by the flooring is carried to the next update
+    let reward: Decimal256 = Decimal256::from_uint256(staker_info.weight)
+        * (state.global_reward_index - staker_info.reward_index)
+        + staker_info.reward_dust;
+    let pending_reward: Uint256 = Uint256::one() * reward;
 
     staker_info.reward_index = state.global_reward_index;
     staker_info.pending_reward += pending_reward;
+    staker_info.reward_dust = reward - Decimal256::from_uint256(pending_reward);
+}
+
+// the fraction of a unit carried by a removed staker goes back to the distribution
+fn release_reward_dust(state: &mut State, staker_info: &StakerInfo) {
+    state.reward_remainder += staker_info.reward_dust;
 }
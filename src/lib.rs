@@ -1,5 +1,6 @@
 pub mod contract;
 mod error;
+pub mod events;
 mod handle;
 pub mod msg;
 mod query;
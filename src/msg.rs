@@ -1,28 +1,162 @@
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use cw20::Cw20ReceiveMsg;
+use cosmwasm_std::Binary;
+use cw20::{Cw20ReceiveMsg, Expiration};
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
+use terraswap::asset::AssetInfo;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub miaw_token: String,
     pub miaw_lp_token: String,
-    pub distribution_schedule: Vec<(u64, u64, Uint256)>,
+    pub distribution_schedule: Vec<ScheduleEntry>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
     Receive(Cw20ReceiveMsg),
-    Unbond { amount: Option<Uint256> },
+    /// `staker` lets an approved operator unbond on behalf of the staker,
+    /// the LP tokens are always returned to the staker. `token_id` unbonds
+    /// from a position instead, by its owner or an operator of the owner
+    Unbond {
+        amount: Option<Uint256>,
+        staker: Option<String>,
+        token_id: Option<String>,
+    },
     BondHook {},
-    ClaimRewards {},
+    /// `token_id` claims the rewards of a position, paid to its owner
+    ClaimRewards {
+        staker: Option<String>,
+        token_id: Option<String>,
+    },
+    /// Claims the rewards of a batch of stakers ordered by address, then of the
+    /// positions ordered by token ID, only by the owner or the keeper. The cursor
+    /// of the next batch is returned in the `next_cursor` attribute, prefixed by
+    /// `position:` once the positions are reached
+    DistributeToStakers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    UpdateConfig(UpdateConfigMsg),
+    /// Approves an operator to unbond and claim on behalf of the sender, also for
+    /// the positions of the sender. Operators cannot transfer the positions
+    ApproveOperator {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    RevokeOperator {
+        operator: String,
+    },
+    /// Sets the secondary rewarder notified on every bond and claim, none removes it
+    SetRewarder {
+        rewarder: Option<String>,
+    },
+    /// Sets the keeper allowed to distribute the rewards to the stakers, none removes it
+    SetKeeper {
+        keeper: Option<String>,
+    },
+    /// Registers a contract notified with `StakeChangedHookMsg` on every bond change,
+    /// a failing hook reverts the staking action unless `revert_on_failure` is false.
+    /// The hooks allowed to fail run with `gas_limit`, 300,000 by default
+    AddHook {
+        addr: String,
+        revert_on_failure: Option<bool>,
+        gas_limit: Option<u64>,
+    },
+    RemoveHook {
+        addr: String,
+    },
+    UpdateAllowlist {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    RecoverUndistributed {
+        recipient: String,
+    },
+    SweepTokens {
+        asset: AssetInfo,
+        recipient: String,
+        amount: Option<Uint256>,
+    },
+    Kick {
+        staker: String,
+    },
+    TransferPosition {
+        recipient: String,
+        amount: Option<Uint256>,
+        include_rewards: Option<bool>,
+    },
+    WithdrawVested {},
+    WithdrawVestedEarly {},
+    /// Streams reward tokens already sent to the contract: the left stream and
+    /// the amount are spread again over `reward_deposit_duration`
+    NotifyRewardAmount {
+        amount: Uint256,
+    },
+    /// Registers the cw20 receipt token minted 1:1 with bonded LP tokens.
+    /// The staking contract must be its minter and allowed to burn the
+    /// receipts of the stakers on unbond.
+    RegisterReceiptToken {
+        receipt_token: String,
+    },
+    /// Called by the receipt token on every transfer to move the
+    /// underlying position along with the receipts
+    ReceiptTransferHook {
+        from: String,
+        to: String,
+        amount: Uint256,
+    },
+    /// cw721 transfer of a position along with its pending reward, by its owner,
+    /// a spender approved for the token or an operator approved for all of them
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    /// cw721 transfer of a position to a contract, notified with `Cw721ReceiveMsg`
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    /// cw721 approval of a spender to transfer a position, by its owner or
+    /// an operator approved for all of them
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    /// cw721 approval of an operator to transfer all the positions of the sender
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    RevokeAll {
+        operator: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Cw20HookMsg {
-    Bond {},
+    Bond {
+        referrer: Option<String>,
+    },
+    /// Bonds into a new position minted as a cw721 token to the sender.
+    /// The rewarder and the hooks see the bond of the positions with the bond of their owner
+    BondPosition {},
+    /// Distributes the sent reward tokens to the stakers, at once or streamed
+    /// over `reward_deposit_duration`. Only the owner's deposits restart the
+    /// window of an active stream, others are streamed until its end time
+    DepositReward {},
+    /// Funds the referral rewards, paid on top of the claims of the referred
+    /// stakers while the reserve lasts
+    DepositReferralReserve {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -30,28 +164,147 @@ pub enum Cw20HookMsg {
 pub enum QueryMsg {
     Config {},
     State {},
+    Status {},
     StakerInfo {
         staker: String,
     },
+    /// At most `max_limit` addresses per query
+    StakersByAddress {
+        addresses: Vec<String>,
+    },
+    /// `start_after` takes the `next_cursor` of the previous page, an address,
+    /// or an opaque cursor when sorted by bond amount. `min_bond` requires
+    /// sorting by bond amount
     StakersInfo {
         start_after: Option<String>,
         limit: Option<u32>,
         order_by: Option<OrderBy>,
+        sort_by: Option<StakersSortBy>,
+        min_bond: Option<Uint256>,
+    },
+    RewardDust {},
+    UndistributedReward {},
+    SimulateBond {
+        staker: String,
+        amount: Uint256,
+        time: Option<u64>,
+    },
+    SimulateUnbond {
+        staker: String,
+        amount: Option<Uint256>,
+        time: Option<u64>,
+    },
+    SimulateClaim {
+        staker: String,
+        time: Option<u64>,
+    },
+    Vesting {
+        staker: String,
+    },
+    Referees {
+        referrer: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Emission rate and cumulative amount emitted by the distribution schedule
+    /// at each of the timestamps
+    EmissionCurve {
+        timestamps: Vec<u64>,
+    },
+    ReferralEarnings {
+        referrer: String,
+    },
+    Allowlist {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Operators approved by the staker through `ApproveOperator`
+    Operators {
+        staker: String,
+    },
+    Hooks {},
+    /// cw721 queries of the positions
+    OwnerOf {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    NftInfo {
+        token_id: String,
+    },
+    AllNftInfo {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    AllTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    NumTokens {},
+    ContractInfo {},
+    Approval {
+        token_id: String,
+        spender: String,
+        include_expired: Option<bool>,
+    },
+    Approvals {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    AllOperators {
+        owner: String,
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
     },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
+    pub owner: String,
     pub miaw_token: String,
     pub miaw_lp_token: String,
-    pub distribution_schedule: Vec<(u64, u64, Uint256)>,
+    pub distribution_schedule: Vec<ScheduleEntry>,
+    pub page_limits: PageLimits,
+    pub undistributed_policy: UndistributedPolicy,
+    pub vesting: VestingConfig,
+    pub boost: BoostConfig,
+    pub reward_fees: RewardFees,
+    pub bond_restrictions: BondRestrictions,
+    pub farm_window: FarmWindow,
+    pub paused: bool,
+    pub receipt_token: Option<String>,
+    pub rewarder: Option<String>,
+    pub reward_deposit_duration: u64,
+    pub keeper: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct StateResponse {
     pub last_distributed: u64,
     pub total_bond_amount: Uint256,
+    pub total_weight: Uint256,
     pub global_reward_index: Decimal256,
+    pub staker_count: u64,
+    pub cumulative_reward_distributed: Uint256,
+    pub cumulative_reward_claimed: Uint256,
+    pub cumulative_bond_amount: Uint256,
+    pub cumulative_unbond_amount: Uint256,
+    pub reward_stream_amount: Uint256,
+    pub reward_stream_end_time: u64,
+    pub reward_rate: Decimal256,
+    pub referral_reserve: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatusResponse {
+    pub status: FarmStatus,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -60,15 +313,264 @@ pub struct StakerInfoResponse {
     pub reward_index: Decimal256,
     pub bond_amount: Uint256,
     pub pending_reward: Uint256,
+    pub weight: Uint256,
+    pub pending_fee: Uint256,
+    // pending rewards of the secondary rewarder, only filled by the StakerInfo query
+    pub rewarder_rewards: Option<Vec<RewarderReward>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct StakersInfoResponse {
     pub stakers: Vec<StakerInfoResponse>,
+    /// not counted when filtered by `min_bond`
+    pub total_count: Option<u64>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardDustResponse {
+    pub reward_remainder: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UndistributedRewardResponse {
+    pub amount: Uint256,
+    pub policy: UndistributedPolicy,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulationResponse {
+    pub staker_info: StakerInfoResponse,
+    pub state: StateResponse,
+    // net of the fee, the referral reward is paid from the referral reserve
+    pub reward_amount: Uint256,
+    // reward fee of a claim, deposit fee of a bond
+    pub fee_amount: Uint256,
+    pub referral_amount: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EmissionCurveResponse {
+    pub points: Vec<EmissionPointResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EmissionPointResponse {
+    pub time: u64,
+    /// reward tokens emitted per second
+    pub emission_rate: Decimal256,
+    /// reward tokens emitted from the start of the schedule until the time
+    pub emitted_amount: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingScheduleResponse {
+    pub start_time: u64,
+    pub end_time: u64,
+    pub amount: Uint256,
+    pub withdrawn_amount: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingResponse {
+    pub staker: String,
+    pub schedules: Vec<VestingScheduleResponse>,
+    pub claimable_amount: Uint256,
+    pub locked_amount: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ApprovalsResponse {
+    pub staker: String,
+    pub approvals: Vec<ApprovalResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ApprovalResponse {
+    pub operator: String,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RefereesResponse {
+    pub referees: Vec<String>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReferralEarningsResponse {
+    pub referrer: String,
+    pub amount: Uint256,
+}
+
+/// Message executed on the secondary rewarder, which pays its own tokens.
+/// `new_bond` is the bond of the staker including the positions it holds and
+/// `pending_miaw` is the MIAW reward of the staker or position settled by the interaction.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RewarderExecuteMsg {
+    OnMiawReward {
+        staker: String,
+        new_bond: Uint256,
+        pending_miaw: Uint256,
+    },
+}
+
+/// Query answered by the secondary rewarder with `RewarderPendingRewardsResponse`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RewarderQueryMsg {
+    PendingRewards { staker: String },
+}
+
+/// Query answered by a `BoostSource::Staking` boost contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BoostQueryMsg {
+    /// Returns `StakedBalanceResponse`
+    StakedBalance { address: String },
+    /// Returns `TotalStakedResponse`
+    TotalStaked {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakedBalanceResponse {
+    pub balance: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalStakedResponse {
+    pub total: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewarderPendingRewardsResponse {
+    pub rewards: Vec<RewarderReward>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewarderReward {
+    pub asset: AssetInfo,
+    pub amount: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HooksResponse {
+    pub hooks: Vec<HookResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HookResponse {
+    pub addr: String,
+    pub revert_on_failure: bool,
+    pub gas_limit: Option<u64>,
+}
+
+/// Bond of the staker including the positions it holds, before and after the change
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakeChangedHookMsg {
+    pub addr: String,
+    pub old_bond: Uint256,
+    pub new_bond: Uint256,
+}
+
+/// Message executed on the hook contracts
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StakeChangedExecuteMsg {
+    StakeChangedHook(StakeChangedHookMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw721ReceiveMsg {
+    pub sender: String,
+    pub token_id: String,
+    pub msg: Binary,
+}
+
+/// Message executed on the contracts receiving a position through `SendNft`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw721ReceiverExecuteMsg {
+    ReceiveNft(Cw721ReceiveMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerOfResponse {
+    pub owner: String,
+    pub approvals: Vec<NftApproval>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NftApproval {
+    pub spender: String,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NftApprovalResponse {
+    pub approval: NftApproval,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NftApprovalsResponse {
+    pub approvals: Vec<NftApproval>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorsResponse {
+    pub operators: Vec<NftApproval>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NftInfoResponse {
+    pub token_uri: Option<String>,
+    pub extension: PositionResponse,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllNftInfoResponse {
+    pub access: OwnerOfResponse,
+    pub info: NftInfoResponse,
 }
 
+/// Position of a token, with the pending reward settled at the query time
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct PositionResponse {
+    pub bond_amount: Uint256,
+    pub bond_time: u64,
+    pub reward_index: Decimal256,
+    pub pending_reward: Uint256,
+    pub weight: Uint256,
+    pub pending_fee: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokensResponse {
+    pub tokens: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NumTokensResponse {
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractInfoResponse {
+    pub name: String,
+    pub symbol: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllowlistResponse {
+    pub addresses: Vec<String>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    pub owner: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -76,3 +578,149 @@ pub enum OrderBy {
     Asc,
     Desc,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StakersSortBy {
+    Address,
+    BondAmount,
+}
+
+/// What happens to the rewards emitted while nothing is bonded
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UndistributedPolicy {
+    /// distributed to the stakers of the next active period
+    CarryForward,
+    /// recoverable by the owner via `RecoverUndistributed`
+    #[default]
+    Reclaim,
+}
+
+/// Distribution schedule entry `[start_time, end_time, amount, curve]` emitting
+/// `amount` over the period along the curve, linear when the curve is omitted
+#[derive(Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScheduleEntry(
+    pub u64,
+    pub u64,
+    pub Uint256,
+    #[serde(default)] pub EmissionCurve,
+);
+
+// serialized as a plain tuple, the json serializer does not support tuple structs
+impl Serialize for ScheduleEntry {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.0, self.1, self.2, &self.3).serialize(serializer)
+    }
+}
+
+impl ScheduleEntry {
+    pub fn linear(start_time: u64, end_time: u64, amount: Uint256) -> Self {
+        ScheduleEntry(start_time, end_time, amount, EmissionCurve::Linear)
+    }
+}
+
+/// Shape of the emission rate over a schedule entry
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EmissionCurve {
+    #[default]
+    Linear,
+    /// the rate halves every `period` seconds
+    Halving { period: u64 },
+    /// the rate is multiplied by `decay` every `step` seconds
+    SteppedDecay { step: u64, decay: Decimal256 },
+    /// relative rates at offsets in seconds from the start time, interpolated
+    /// linearly in between. The offsets start at zero and end at the length of the entry.
+    PiecewiseLinear { points: Vec<(u64, Decimal256)> },
+}
+
+/// Restrictions on bonding, e.g. for a private launch phase
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct BondRestrictions {
+    /// only the addresses on the allowlist can bond
+    pub allowlist_enabled: bool,
+    /// max bond amount of each staker, zero for no cap
+    pub max_bond_per_staker: Uint256,
+    /// max total bond amount, zero for no cap
+    pub max_total_bond: Uint256,
+    /// share of the bonded LP tokens sent to the treasury
+    pub deposit_fee_rate: Decimal256,
+    pub treasury: Option<String>,
+}
+
+/// Settings updated by the owner, the unset ones are left unchanged
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct UpdateConfigMsg {
+    pub owner: Option<String>,
+    pub page_limits: Option<PageLimits>,
+    pub undistributed_policy: Option<UndistributedPolicy>,
+    pub vesting: Option<VestingConfig>,
+    pub boost: Option<BoostConfig>,
+    pub reward_fees: Option<RewardFees>,
+    pub bond_restrictions: Option<BondRestrictions>,
+    pub farm_window: Option<FarmWindow>,
+    pub paused: Option<bool>,
+    pub reward_deposit_duration: Option<u64>,
+}
+
+/// Page size of the listing queries and batch messages
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PageLimits {
+    pub max_limit: u32,
+    /// used when no limit is given, at most `max_limit`
+    pub default_limit: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct BoostConfig {
+    /// contract whose balances boost the weight of the stakers, none disables the boost
+    pub contract: Option<String>,
+    pub source: BoostSource,
+}
+
+/// How the boost balances are queried from the boost contract
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BoostSource {
+    /// cw20 `Balance` of the staker over the `TokenInfo` total supply
+    #[default]
+    Cw20,
+    /// `BoostQueryMsg` answered by a governance or staking contract
+    Staking,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct VestingConfig {
+    /// claimed rewards vest linearly over this many seconds, zero transfers them at once
+    pub duration: u64,
+    /// share of the unvested amount forfeited by `WithdrawVestedEarly`
+    pub early_withdraw_penalty: Decimal256,
+}
+
+/// Shares of the claimed rewards going to the referrer and the fee collector,
+/// the referral share is paid on top of the claim from the referral reserve
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct RewardFees {
+    pub referral_rate: Decimal256,
+    pub reward_fee_rate: Decimal256,
+    /// required when the reward fee rate is not zero
+    pub fee_collector: Option<String>,
+}
+
+/// Bonding window of the farm, each bound defaults to the distribution schedule when unset
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct FarmWindow {
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FarmStatus {
+    Upcoming,
+    Active,
+    Ended,
+    /// bonding is paused by the owner, unbonding and claiming are still open
+    Paused,
+}
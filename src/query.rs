@@ -1,10 +1,28 @@
-use cosmwasm_std::{Addr, CanonicalAddr, Deps, Env};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{
+    to_binary, Addr, CanonicalAddr, Deps, Env, QueryRequest, StdResult, Timestamp, WasmQuery,
+};
 
 use crate::error::ContractError;
-use crate::handle::{compute_reward, compute_staker_reward};
-use crate::msg::{ConfigResponse, OrderBy, StakerInfoResponse, StakersInfoResponse, StateResponse};
+use crate::handle::{
+    apply_bond_restrictions, compute_reward, compute_staker_reward, decrease_bond_amount,
+    emission_rate, emitted_amount, increase_bond_amount, split_claim_amount, update_staker_weight,
+};
+use crate::msg::{
+    AllNftInfoResponse, AllowlistResponse, ApprovalsResponse, ConfigResponse, ContractInfoResponse,
+    EmissionCurveResponse, EmissionPointResponse, HooksResponse, NftApproval, NftApprovalResponse,
+    NftApprovalsResponse, NftInfoResponse, NumTokensResponse, OperatorsResponse, OrderBy,
+    OwnerOfResponse, RefereesResponse, ReferralEarningsResponse, RewardDustResponse,
+    RewarderPendingRewardsResponse, RewarderQueryMsg, SimulationResponse, StakerInfoResponse,
+    StakersInfoResponse, StakersSortBy, StateResponse, StatusResponse, TokensResponse,
+    UndistributedRewardResponse, VestingResponse, VestingScheduleResponse,
+};
 use crate::state::{
-    read_stakers_with_updated_reward, Config, StakerInfo, State, CONFIG, STAKER_INFO, STATE,
+    decode_bond_cursor, encode_bond_cursor, positions, read_allowlist, read_approvals, read_hooks,
+    read_nft_operators, read_position_ids, read_referees, read_stakers_by_bond_with_updated_reward,
+    read_stakers_with_updated_reward, staker_infos, Config, Position, StakerInfo, State,
+    VestingSchedule, CONFIG, POSITION_NAME, POSITION_SYMBOL, REFERRAL_EARNINGS, REFERRERS, STATE,
+    VESTING_SCHEDULES,
 };
 
 pub fn query_config(deps: Deps) -> Result<ConfigResponse, ContractError> {
@@ -19,22 +37,289 @@ pub fn query_state(deps: Deps) -> Result<StateResponse, ContractError> {
     Ok(state.as_res())
 }
 
+pub fn query_status(deps: Deps, env: Env) -> Result<StatusResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let (start_time, end_time) = config.farm_window();
+
+    Ok(StatusResponse {
+        status: config.farm_status(env.block.time.seconds()),
+        start_time,
+        end_time,
+    })
+}
+
+pub fn query_reward_dust(deps: Deps, env: Env) -> Result<RewardDustResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut state: State = STATE.load(deps.storage)?;
+    compute_reward(&config, &mut state, env.block.time.seconds());
+
+    Ok(RewardDustResponse {
+        reward_remainder: state.reward_remainder,
+    })
+}
+
+pub fn query_undistributed_reward(
+    deps: Deps,
+    env: Env,
+) -> Result<UndistributedRewardResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut state: State = STATE.load(deps.storage)?;
+    compute_reward(&config, &mut state, env.block.time.seconds());
+
+    Ok(UndistributedRewardResponse {
+        amount: state.undistributed_amount,
+        policy: config.undistributed_policy,
+    })
+}
+
+pub fn query_vesting(
+    deps: Deps,
+    env: Env,
+    staker: String,
+) -> Result<VestingResponse, ContractError> {
+    let staker_addr: Addr = deps.api.addr_validate(&staker)?;
+    let staker_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    let current_time: u64 = env.block.time.seconds();
+
+    let schedules: Vec<VestingSchedule> = VESTING_SCHEDULES
+        .may_load(deps.storage, staker_raw.as_slice())?
+        .unwrap_or_default();
+
+    let mut claimable_amount: Uint256 = Uint256::zero();
+    let mut locked_amount: Uint256 = Uint256::zero();
+    for schedule in schedules.iter() {
+        let schedule_claimable_amount: Uint256 = schedule.claimable_amount(current_time);
+        claimable_amount += schedule_claimable_amount;
+        locked_amount += schedule.amount - schedule.withdrawn_amount - schedule_claimable_amount;
+    }
+
+    Ok(VestingResponse {
+        staker: staker_addr.to_string(),
+        schedules: schedules
+            .iter()
+            .map(|schedule| schedule.as_res())
+            .collect::<Vec<VestingScheduleResponse>>(),
+        claimable_amount,
+        locked_amount,
+    })
+}
+
+pub fn query_referees(
+    deps: Deps,
+    referrer: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<RefereesResponse, ContractError> {
+    let referrer_addr: Addr = deps.api.addr_validate(&referrer)?;
+    let referrer_raw: CanonicalAddr = deps.api.addr_canonicalize(referrer_addr.as_str())?;
+    let start_after: Option<CanonicalAddr> = match start_after {
+        Some(addr) => {
+            let addr: Addr = deps
+                .api
+                .addr_validate(&addr)
+                .map_err(|_| ContractError::InvalidCursor {})?;
+            Some(deps.api.addr_canonicalize(addr.as_str())?)
+        }
+        None => None,
+    };
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(config.default_limit).min(config.max_limit) as usize;
+
+    let referees: Vec<String> =
+        read_referees(deps.storage, deps.api, &referrer_raw, start_after, limit)?;
+
+    // a full page may be followed by more referees
+    let next_cursor: Option<String> = if referees.len() == limit {
+        referees.last().cloned()
+    } else {
+        None
+    };
+
+    Ok(RefereesResponse {
+        referees,
+        next_cursor,
+    })
+}
+
+pub fn query_allowlist(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<AllowlistResponse, ContractError> {
+    let start_after: Option<CanonicalAddr> = match start_after {
+        Some(addr) => {
+            let addr: Addr = deps
+                .api
+                .addr_validate(&addr)
+                .map_err(|_| ContractError::InvalidCursor {})?;
+            Some(deps.api.addr_canonicalize(addr.as_str())?)
+        }
+        None => None,
+    };
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(config.default_limit).min(config.max_limit) as usize;
+
+    let addresses: Vec<String> = read_allowlist(deps.storage, deps.api, start_after, limit)?;
+
+    // a full page may be followed by more addresses
+    let next_cursor: Option<String> = if addresses.len() == limit {
+        addresses.last().cloned()
+    } else {
+        None
+    };
+
+    Ok(AllowlistResponse {
+        addresses,
+        next_cursor,
+    })
+}
+
+pub fn query_operators(
+    deps: Deps,
+    env: Env,
+    staker: String,
+) -> Result<ApprovalsResponse, ContractError> {
+    let staker_addr: Addr = deps.api.addr_validate(&staker)?;
+    let staker_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+
+    Ok(ApprovalsResponse {
+        staker: staker_addr.to_string(),
+        approvals: read_approvals(deps.storage, deps.api, &staker_raw, &env.block)?,
+    })
+}
+
+pub fn query_hooks(deps: Deps) -> Result<HooksResponse, ContractError> {
+    Ok(HooksResponse {
+        hooks: read_hooks(deps.storage, deps.api)?,
+    })
+}
+
+pub fn query_emission_curve(
+    deps: Deps,
+    timestamps: Vec<u64>,
+) -> Result<EmissionCurveResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if timestamps.len() > config.max_limit as usize {
+        return Err(ContractError::TooManySamples {
+            max: config.max_limit,
+        });
+    }
+
+    let points: Vec<EmissionPointResponse> = timestamps
+        .into_iter()
+        .map(|time| {
+            let mut rate: Decimal256 = Decimal256::zero();
+            let mut emitted: Uint256 = Uint256::zero();
+            for s in config.distribution_schedule.iter() {
+                rate += emission_rate(s, time);
+                emitted += emitted_amount(s, time);
+            }
+
+            EmissionPointResponse {
+                time,
+                emission_rate: rate,
+                emitted_amount: emitted,
+            }
+        })
+        .collect();
+
+    Ok(EmissionCurveResponse { points })
+}
+
+pub fn query_referral_earnings(
+    deps: Deps,
+    referrer: String,
+) -> Result<ReferralEarningsResponse, ContractError> {
+    let referrer_addr: Addr = deps.api.addr_validate(&referrer)?;
+    let referrer_raw: CanonicalAddr = deps.api.addr_canonicalize(referrer_addr.as_str())?;
+
+    Ok(ReferralEarningsResponse {
+        referrer: referrer_addr.to_string(),
+        amount: REFERRAL_EARNINGS
+            .may_load(deps.storage, referrer_raw.as_slice())?
+            .unwrap_or_default(),
+    })
+}
+
 pub fn query_staker_info(
     deps: Deps,
     env: Env,
     staker: String,
 ) -> Result<StakerInfoResponse, ContractError> {
     let staker_addr: Addr = deps.api.addr_validate(&staker)?;
-    let staker_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
 
     let config: Config = CONFIG.load(deps.storage)?;
     let mut state: State = STATE.load(deps.storage)?;
-    let mut staker_info: StakerInfo = STAKER_INFO.load(deps.storage, staker_raw.as_slice())?;
+    compute_reward(&config, &mut state, env.block.time.seconds());
+
+    let mut staker_info: StakerInfoResponse =
+        read_staker_with_updated_reward(deps, &config, &state, &staker_addr)?;
+
+    // Extend with the pending rewards of the secondary rewarder, left out when it fails
+    if let Some(rewarder) = &config.rewarder {
+        let res: StdResult<RewarderPendingRewardsResponse> =
+            deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: deps.api.addr_humanize(rewarder)?.to_string(),
+                msg: to_binary(&RewarderQueryMsg::PendingRewards {
+                    staker: staker_addr.to_string(),
+                })?,
+            }));
+        staker_info.rewarder_rewards = res.ok().map(|res| res.rewards);
+    }
+
+    Ok(staker_info)
+}
 
+pub fn query_stakers_by_address(
+    deps: Deps,
+    env: Env,
+    addresses: Vec<String>,
+) -> Result<StakersInfoResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if addresses.len() > config.max_limit as usize {
+        return Err(ContractError::TooManyAddresses {
+            max: config.max_limit,
+        });
+    }
+
+    let staker_addrs: Vec<Addr> = addresses
+        .iter()
+        .map(|addr| deps.api.addr_validate(addr))
+        .collect::<StdResult<Vec<Addr>>>()?;
+
+    // update global reward
+    let mut state: State = STATE.load(deps.storage)?;
     compute_reward(&config, &mut state, env.block.time.seconds());
-    compute_staker_reward(&state, &mut staker_info);
 
-    Ok(staker_info.as_res(&staker_addr))
+    let stakers: Vec<StakerInfoResponse> = staker_addrs
+        .iter()
+        .map(|staker_addr| read_staker_with_updated_reward(deps, &config, &state, staker_addr))
+        .collect::<Result<Vec<StakerInfoResponse>, ContractError>>()?;
+
+    Ok(StakersInfoResponse {
+        total_count: Some(stakers.len() as u64),
+        stakers,
+        next_cursor: None,
+    })
+}
+
+// unknown stakers are reported with zero bond at the current global index
+fn read_staker_with_updated_reward(
+    deps: Deps,
+    config: &Config,
+    state: &State,
+    staker_addr: &Addr,
+) -> Result<StakerInfoResponse, ContractError> {
+    let staker_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    let mut staker_info: StakerInfo = staker_infos()
+        .may_load(deps.storage, staker_raw.as_slice())?
+        .unwrap_or_default();
+
+    compute_staker_reward(state, &mut staker_info);
+
+    Ok(staker_info.as_res(staker_addr, config.reward_fee_rate))
 }
 
 pub fn query_stakers_info(
@@ -43,22 +328,414 @@ pub fn query_stakers_info(
     start_after: Option<String>,
     limit: Option<u32>,
     order_by: Option<OrderBy>,
+    sort_by: Option<StakersSortBy>,
+    min_bond: Option<Uint256>,
 ) -> Result<StakersInfoResponse, ContractError> {
-    let start_after: Option<CanonicalAddr> =
-        start_after.map(|addr| deps.api.addr_canonicalize(addr.as_str()).unwrap());
-
     // update global reward
     let config: Config = CONFIG.load(deps.storage)?;
     let mut state: State = STATE.load(deps.storage)?;
     compute_reward(&config, &mut state, env.block.time.seconds());
 
-    let res: StakersInfoResponse = read_stakers_with_updated_reward(
-        deps.storage,
-        deps.api,
+    let limit = limit.unwrap_or(config.default_limit).min(config.max_limit) as usize;
+    let order_by = order_by.unwrap_or(OrderBy::Desc);
+
+    // the filtered stakers are listed through the bond index
+    let sort_by_bond: bool = sort_by == Some(StakersSortBy::BondAmount);
+    if min_bond.is_some() && !sort_by_bond {
+        return Err(ContractError::MinBondRequiresBondOrder {});
+    }
+    let stakers: Vec<StakerInfoResponse> = if sort_by_bond {
+        let cursor: Option<Vec<u8>> = match start_after {
+            Some(cursor) => Some(decode_bond_cursor(&cursor)?),
+            None => None,
+        };
+        read_stakers_by_bond_with_updated_reward(
+            deps.storage,
+            deps.api,
+            &state,
+            config.reward_fee_rate,
+            cursor,
+            limit,
+            order_by,
+            min_bond,
+        )?
+    } else {
+        let start_after: Option<CanonicalAddr> = match start_after {
+            Some(addr) => {
+                let addr: Addr = deps
+                    .api
+                    .addr_validate(&addr)
+                    .map_err(|_| ContractError::InvalidCursor {})?;
+                Some(deps.api.addr_canonicalize(addr.as_str())?)
+            }
+            None => None,
+        };
+        read_stakers_with_updated_reward(
+            deps.storage,
+            deps.api,
+            &state,
+            config.reward_fee_rate,
+            start_after,
+            limit,
+            order_by,
+        )?
+    };
+
+    // a full page may be followed by more stakers
+    let next_cursor: Option<String> = match stakers.last() {
+        Some(last) if stakers.len() == limit && sort_by_bond => Some(encode_bond_cursor(
+            &last.bond_amount,
+            &deps.api.addr_canonicalize(&last.staker)?,
+        )),
+        Some(last) if stakers.len() == limit => Some(last.staker.clone()),
+        _ => None,
+    };
+
+    Ok(StakersInfoResponse {
+        stakers,
+        total_count: match min_bond {
+            Some(_) => None,
+            None => Some(state.staker_count),
+        },
+        next_cursor,
+    })
+}
+
+pub fn query_simulate_bond(
+    deps: Deps,
+    env: Env,
+    staker: String,
+    amount: Uint256,
+    time: Option<u64>,
+) -> Result<SimulationResponse, ContractError> {
+    let staker_addr: Addr = deps.api.addr_validate(&staker)?;
+    let staker_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+
+    let mut state: State = STATE.load(deps.storage)?;
+    let mut staker_info: StakerInfo =
+        match staker_infos().may_load(deps.storage, staker_raw.as_slice())? {
+            Some(staker_info) => staker_info,
+            None => {
+                state.staker_count += 1;
+                StakerInfo::default()
+            }
+        };
+
+    let config: Config = simulate_reward(deps, &env, time, &mut state, &mut staker_info)?;
+
+    // the bond is restricted as it would be at the simulated time
+    let mut env: Env = env;
+    if let Some(time) = time {
+        env.block.time = Timestamp::from_seconds(time);
+    }
+    let (amount, deposit_fee): (Uint256, Uint256) = apply_bond_restrictions(
+        deps,
+        &env,
+        &config,
         &state,
-        start_after,
-        limit,
-        order_by,
+        &staker_raw,
+        &staker_info,
+        amount,
     )?;
-    Ok(res)
+
+    increase_bond_amount(&mut state, &mut staker_info, amount);
+    update_staker_weight(deps, &config, &mut state, &staker_addr, &mut staker_info)?;
+
+    Ok(SimulationResponse {
+        staker_info: staker_info.as_res(&staker_addr, config.reward_fee_rate),
+        state: state.as_res(),
+        reward_amount: Uint256::zero(),
+        fee_amount: deposit_fee,
+        referral_amount: Uint256::zero(),
+    })
+}
+
+pub fn query_simulate_unbond(
+    deps: Deps,
+    env: Env,
+    staker: String,
+    amount: Option<Uint256>,
+    time: Option<u64>,
+) -> Result<SimulationResponse, ContractError> {
+    let staker_addr: Addr = deps.api.addr_validate(&staker)?;
+    let staker_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+
+    let mut state: State = STATE.load(deps.storage)?;
+    let mut staker_info: StakerInfo = staker_infos().load(deps.storage, staker_raw.as_slice())?;
+
+    let amount_to_unbond: Uint256 = match amount {
+        Some(amount) if staker_info.bond_amount < amount => {
+            return Err(ContractError::InvalidUnbondAmount {})
+        }
+        Some(amount) => amount,
+        None => staker_info.bond_amount,
+    };
+
+    let config: Config = simulate_reward(deps, &env, time, &mut state, &mut staker_info)?;
+    decrease_bond_amount(&mut state, &mut staker_info, amount_to_unbond);
+    update_staker_weight(deps, &config, &mut state, &staker_addr, &mut staker_info)?;
+    if staker_info.pending_reward.is_zero() && staker_info.bond_amount.is_zero() {
+        state.staker_count -= 1;
+    }
+
+    Ok(SimulationResponse {
+        staker_info: staker_info.as_res(&staker_addr, config.reward_fee_rate),
+        state: state.as_res(),
+        reward_amount: Uint256::zero(),
+        fee_amount: Uint256::zero(),
+        referral_amount: Uint256::zero(),
+    })
+}
+
+pub fn query_simulate_claim(
+    deps: Deps,
+    env: Env,
+    staker: String,
+    time: Option<u64>,
+) -> Result<SimulationResponse, ContractError> {
+    let staker_addr: Addr = deps.api.addr_validate(&staker)?;
+    let staker_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+
+    let mut state: State = STATE.load(deps.storage)?;
+    let mut staker_info: StakerInfo = staker_infos().load(deps.storage, staker_raw.as_slice())?;
+
+    let config: Config = simulate_reward(deps, &env, time, &mut state, &mut staker_info)?;
+    update_staker_weight(deps, &config, &mut state, &staker_addr, &mut staker_info)?;
+
+    let amount: Uint256 = staker_info.pending_reward;
+    staker_info.pending_reward = Uint256::zero();
+    if staker_info.bond_amount.is_zero() {
+        state.staker_count -= 1;
+    }
+
+    // the referral reward is funded from the reserve and stays owed to the referrer,
+    // who becomes a staker if not one yet
+    let referrer_raw: Option<CanonicalAddr> =
+        REFERRERS.may_load(deps.storage, staker_raw.as_slice())?;
+    let (reward_amount, fee_amount, referral_amount) =
+        split_claim_amount(&config, &state, amount, referrer_raw.is_some());
+    if let Some(referrer_raw) = referrer_raw {
+        if !referral_amount.is_zero() {
+            state.referral_reserve = state.referral_reserve - referral_amount;
+            state.cumulative_reward_distributed += referral_amount;
+            if staker_infos()
+                .may_load(deps.storage, referrer_raw.as_slice())?
+                .is_none()
+            {
+                state.staker_count += 1;
+            }
+        }
+    }
+    state.cumulative_reward_claimed += amount;
+
+    Ok(SimulationResponse {
+        staker_info: staker_info.as_res(&staker_addr, config.reward_fee_rate),
+        state: state.as_res(),
+        reward_amount,
+        fee_amount,
+        referral_amount,
+    })
+}
+
+// compute global & staker reward at the given time (defaults to the current block time)
+fn simulate_reward(
+    deps: Deps,
+    env: &Env,
+    time: Option<u64>,
+    state: &mut State,
+    staker_info: &mut StakerInfo,
+) -> Result<Config, ContractError> {
+    let current_time: u64 = env.block.time.seconds();
+    let time: u64 = time.unwrap_or(current_time);
+    if time < current_time {
+        return Err(ContractError::InvalidSimulationTime {});
+    }
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    compute_reward(&config, state, time);
+    compute_staker_reward(state, staker_info);
+
+    Ok(config)
+}
+
+pub fn query_owner_of(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: Option<bool>,
+) -> Result<OwnerOfResponse, ContractError> {
+    let position: Position = positions().load(deps.storage, &token_id)?;
+
+    Ok(owner_of(deps, &env, &position, include_expired)?)
+}
+
+pub fn query_nft_info(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+) -> Result<NftInfoResponse, ContractError> {
+    let position: Position = positions().load(deps.storage, &token_id)?;
+
+    Ok(nft_info(deps, &env, position)?)
+}
+
+pub fn query_all_nft_info(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: Option<bool>,
+) -> Result<AllNftInfoResponse, ContractError> {
+    let position: Position = positions().load(deps.storage, &token_id)?;
+
+    Ok(AllNftInfoResponse {
+        access: owner_of(deps, &env, &position, include_expired)?,
+        info: nft_info(deps, &env, position)?,
+    })
+}
+
+pub fn query_tokens(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<TokensResponse, ContractError> {
+    let owner_addr: Addr = deps.api.addr_validate(&owner)?;
+    let owner_raw: CanonicalAddr = deps.api.addr_canonicalize(owner_addr.as_str())?;
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(config.default_limit).min(config.max_limit) as usize;
+
+    Ok(TokensResponse {
+        tokens: read_position_ids(deps.storage, Some(&owner_raw), start_after, limit)?,
+    })
+}
+
+pub fn query_all_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<TokensResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(config.default_limit).min(config.max_limit) as usize;
+
+    Ok(TokensResponse {
+        tokens: read_position_ids(deps.storage, None, start_after, limit)?,
+    })
+}
+
+pub fn query_num_tokens(deps: Deps) -> Result<NumTokensResponse, ContractError> {
+    let state: State = STATE.load(deps.storage)?;
+
+    Ok(NumTokensResponse {
+        count: state.position_count,
+    })
+}
+
+pub fn query_contract_info() -> Result<ContractInfoResponse, ContractError> {
+    Ok(ContractInfoResponse {
+        name: POSITION_NAME.to_string(),
+        symbol: POSITION_SYMBOL.to_string(),
+    })
+}
+
+pub fn query_nft_approval(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    spender: String,
+    include_expired: Option<bool>,
+) -> Result<NftApprovalResponse, ContractError> {
+    let position: Position = positions().load(deps.storage, &token_id)?;
+    let spender_addr: Addr = deps.api.addr_validate(&spender)?;
+
+    let approval: NftApproval = position
+        .approvals_res(deps.api, &env.block, include_expired.unwrap_or(false))?
+        .into_iter()
+        .find(|approval| approval.spender == spender_addr.as_str())
+        .ok_or(ContractError::ApprovalNotFound {})?;
+
+    Ok(NftApprovalResponse { approval })
+}
+
+pub fn query_nft_approvals(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: Option<bool>,
+) -> Result<NftApprovalsResponse, ContractError> {
+    let position: Position = positions().load(deps.storage, &token_id)?;
+
+    Ok(NftApprovalsResponse {
+        approvals: position.approvals_res(
+            deps.api,
+            &env.block,
+            include_expired.unwrap_or(false),
+        )?,
+    })
+}
+
+pub fn query_all_operators(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    include_expired: Option<bool>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<OperatorsResponse, ContractError> {
+    let owner_addr: Addr = deps.api.addr_validate(&owner)?;
+    let owner_raw: CanonicalAddr = deps.api.addr_canonicalize(owner_addr.as_str())?;
+    let start_after: Option<CanonicalAddr> = match start_after {
+        Some(addr) => {
+            let addr: Addr = deps
+                .api
+                .addr_validate(&addr)
+                .map_err(|_| ContractError::InvalidCursor {})?;
+            Some(deps.api.addr_canonicalize(addr.as_str())?)
+        }
+        None => None,
+    };
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(config.default_limit).min(config.max_limit) as usize;
+
+    Ok(OperatorsResponse {
+        operators: read_nft_operators(
+            deps.storage,
+            deps.api,
+            &owner_raw,
+            &env.block,
+            include_expired.unwrap_or(false),
+            start_after,
+            limit,
+        )?,
+    })
+}
+
+fn owner_of(
+    deps: Deps,
+    env: &Env,
+    position: &Position,
+    include_expired: Option<bool>,
+) -> StdResult<OwnerOfResponse> {
+    Ok(OwnerOfResponse {
+        owner: deps.api.addr_humanize(&position.owner)?.to_string(),
+        approvals: position.approvals_res(
+            deps.api,
+            &env.block,
+            include_expired.unwrap_or(false),
+        )?,
+    })
+}
+
+// the position with its pending reward settled at the block time
+fn nft_info(deps: Deps, env: &Env, mut position: Position) -> StdResult<NftInfoResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut state: State = STATE.load(deps.storage)?;
+    compute_reward(&config, &mut state, env.block.time.seconds());
+    compute_staker_reward(&state, &mut position.staker_info);
+
+    Ok(NftInfoResponse {
+        token_uri: None,
+        extension: position.as_res(config.reward_fee_rate),
+    })
 }
@@ -1,35 +1,234 @@
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use cosmwasm_std::{Addr, Api, CanonicalAddr, Order, StdResult, Storage};
-use cw_storage_plus::{Bound, Item, Map};
+use cosmwasm_std::{
+    Addr, Api, Binary, BlockInfo, CanonicalAddr, Order, StdError, StdResult, Storage,
+};
+use cw20::Expiration;
+use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex, PrimaryKey};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     handle::compute_staker_reward,
-    msg::{ConfigResponse, OrderBy, StakerInfoResponse, StakersInfoResponse, StateResponse},
+    msg::{
+        ApprovalResponse, BondRestrictions, BoostConfig, BoostSource, ConfigResponse, FarmStatus,
+        FarmWindow, HookResponse, NftApproval, OrderBy, PageLimits, PositionResponse, RewardFees,
+        ScheduleEntry, StakerInfoResponse, StateResponse, UndistributedPolicy, VestingConfig,
+        VestingScheduleResponse,
+    },
     ContractError,
 };
 
 pub const CONFIG: Item<Config> = Item::new("\u{0}\u{6}config");
 pub const STATE: Item<State> = Item::new("\u{0}\u{5}state");
-pub const STAKER_INFO: Map<&[u8], StakerInfo> = Map::new("staker_info");
+pub const VESTING_SCHEDULES: Map<&[u8], Vec<VestingSchedule>> = Map::new("vesting_schedule");
+pub const REFERRERS: Map<&[u8], CanonicalAddr> = Map::new("referrer");
+pub const REFEREES: Map<(&[u8], &[u8]), bool> = Map::new("referee");
+pub const REFERRAL_EARNINGS: Map<&[u8], Uint256> = Map::new("referral_earnings");
+pub const ALLOWLIST: Map<&[u8], bool> = Map::new("allowlist");
+pub const HOOKS: Map<&[u8], Hook> = Map::new("hook");
+// (staker, operator) -> expiration of the approval
+pub const OPERATORS: Map<(&[u8], &[u8]), Expiration> = Map::new("operator");
+// (owner, operator) -> expiration of the cw721 approval for all the positions of the owner
+pub const NFT_OPERATORS: Map<(&[u8], &[u8]), Expiration> = Map::new("nft_operator");
+
+pub struct StakerInfoIndexes<'a> {
+    pub bond_amount: MultiIndex<'a, (Vec<u8>, Vec<u8>), StakerInfo>,
+}
+
+impl<'a> IndexList<StakerInfo> for StakerInfoIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<StakerInfo>> + '_> {
+        let v: Vec<&dyn Index<StakerInfo>> = vec![&self.bond_amount];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn staker_infos<'a>() -> IndexedMap<'a, &'a [u8], StakerInfo, StakerInfoIndexes<'a>> {
+    let indexes = StakerInfoIndexes {
+        bond_amount: MultiIndex::new(
+            |s, pk| (bond_amount_key(&s.bond_amount), pk),
+            "staker_info",
+            "staker_info__bond_amount",
+        ),
+    };
+    IndexedMap::new("staker_info", indexes)
+}
+
+pub struct PositionIndexes<'a> {
+    pub owner: MultiIndex<'a, (Vec<u8>, Vec<u8>), Position>,
+}
+
+impl<'a> IndexList<Position> for PositionIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Position>> + '_> {
+        let v: Vec<&dyn Index<Position>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+// cw721 positions keyed by token ID
+pub fn positions<'a>() -> IndexedMap<'a, &'a str, Position, PositionIndexes<'a>> {
+    let indexes = PositionIndexes {
+        owner: MultiIndex::new(
+            |p, pk| (p.owner.to_vec(), pk),
+            "position",
+            "position__owner",
+        ),
+    };
+    IndexedMap::new("position", indexes)
+}
+
+// big-endian encoding, so the index is ordered by bond amount
+fn bond_amount_key(amount: &Uint256) -> Vec<u8> {
+    let mut bytes = [0u8; 32];
+    amount.0.to_big_endian(&mut bytes);
+    bytes.to_vec()
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
+    pub owner: CanonicalAddr,
     pub miaw_token: CanonicalAddr,
     pub miaw_lp_token: CanonicalAddr,
-    pub distribution_schedule: Vec<(u64, u64, Uint256)>,
+    pub distribution_schedule: Vec<ScheduleEntry>,
+    pub max_limit: u32,
+    pub default_limit: u32,
+    #[serde(default)]
+    pub undistributed_policy: UndistributedPolicy,
+    // claimed rewards vest linearly over this many seconds, zero transfers them at once
+    #[serde(default)]
+    pub vesting_duration: u64,
+    // share of the locked rewards forfeited on early withdraw, zero disables it
+    #[serde(default)]
+    pub early_withdraw_penalty: Decimal256,
+    // contract whose balances boost the weight of the stakers
+    #[serde(default)]
+    pub boost_contract: Option<CanonicalAddr>,
+    #[serde(default)]
+    pub boost_source: BoostSource,
+    // share of the claimed rewards allocated to the referrer instead of the staker,
+    // zero disables it
+    #[serde(default)]
+    pub referral_rate: Decimal256,
+    // share of the claimed rewards sent to the fee collector
+    #[serde(default)]
+    pub reward_fee_rate: Decimal256,
+    #[serde(default)]
+    pub fee_collector: Option<CanonicalAddr>,
+    #[serde(default)]
+    pub allowlist_enabled: bool,
+    // zero for no cap
+    #[serde(default)]
+    pub max_bond_per_staker: Uint256,
+    #[serde(default)]
+    pub max_total_bond: Uint256,
+    // share of the bonded LP tokens sent to the treasury
+    #[serde(default)]
+    pub deposit_fee_rate: Decimal256,
+    #[serde(default)]
+    pub treasury: Option<CanonicalAddr>,
+    #[serde(default)]
+    pub start_time: Option<u64>,
+    #[serde(default)]
+    pub end_time: Option<u64>,
+    #[serde(default)]
+    pub paused: bool,
+    // cw20 receipt token minted 1:1 with the bonded LP tokens
+    #[serde(default)]
+    pub receipt_token: Option<CanonicalAddr>,
+    // secondary rewarder notified on every bond and claim
+    #[serde(default)]
+    pub rewarder: Option<CanonicalAddr>,
+    // allowed to distribute the rewards to the stakers besides the owner
+    #[serde(default)]
+    pub keeper: Option<CanonicalAddr>,
+    // deposited rewards are streamed over this many seconds, zero distributes them at once
+    #[serde(default)]
+    pub reward_deposit_duration: u64,
 }
 
 impl Config {
     pub fn as_res(&self, api: &dyn Api) -> Result<ConfigResponse, ContractError> {
         let res = ConfigResponse {
+            owner: api.addr_humanize(&self.owner)?.to_string(),
             miaw_token: api.addr_humanize(&self.miaw_token)?.to_string(),
             miaw_lp_token: api.addr_humanize(&self.miaw_lp_token)?.to_string(),
             distribution_schedule: self.distribution_schedule.clone(),
+            page_limits: PageLimits {
+                max_limit: self.max_limit,
+                default_limit: self.default_limit,
+            },
+            undistributed_policy: self.undistributed_policy,
+            vesting: VestingConfig {
+                duration: self.vesting_duration,
+                early_withdraw_penalty: self.early_withdraw_penalty,
+            },
+            boost: BoostConfig {
+                contract: match &self.boost_contract {
+                    Some(boost_contract) => Some(api.addr_humanize(boost_contract)?.to_string()),
+                    None => None,
+                },
+                source: self.boost_source,
+            },
+            reward_fees: RewardFees {
+                referral_rate: self.referral_rate,
+                reward_fee_rate: self.reward_fee_rate,
+                fee_collector: match &self.fee_collector {
+                    Some(fee_collector) => Some(api.addr_humanize(fee_collector)?.to_string()),
+                    None => None,
+                },
+            },
+            bond_restrictions: BondRestrictions {
+                allowlist_enabled: self.allowlist_enabled,
+                max_bond_per_staker: self.max_bond_per_staker,
+                max_total_bond: self.max_total_bond,
+                deposit_fee_rate: self.deposit_fee_rate,
+                treasury: match &self.treasury {
+                    Some(treasury) => Some(api.addr_humanize(treasury)?.to_string()),
+                    None => None,
+                },
+            },
+            farm_window: FarmWindow {
+                start_time: self.start_time,
+                end_time: self.end_time,
+            },
+            paused: self.paused,
+            receipt_token: match &self.receipt_token {
+                Some(receipt_token) => Some(api.addr_humanize(receipt_token)?.to_string()),
+                None => None,
+            },
+            rewarder: match &self.rewarder {
+                Some(rewarder) => Some(api.addr_humanize(rewarder)?.to_string()),
+                None => None,
+            },
+            reward_deposit_duration: self.reward_deposit_duration,
+            keeper: match &self.keeper {
+                Some(keeper) => Some(api.addr_humanize(keeper)?.to_string()),
+                None => None,
+            },
         };
         Ok(res)
     }
+
+    // explicit bounds, or the bounds of the distribution schedule
+    pub fn farm_window(&self) -> (Option<u64>, Option<u64>) {
+        let start_time: Option<u64> = self
+            .start_time
+            .or_else(|| self.distribution_schedule.iter().map(|s| s.0).min());
+        let end_time: Option<u64> = self
+            .end_time
+            .or_else(|| self.distribution_schedule.iter().map(|s| s.1).max());
+
+        (start_time, end_time)
+    }
+
+    pub fn farm_status(&self, time: u64) -> FarmStatus {
+        let (start_time, end_time) = self.farm_window();
+        match (start_time, end_time) {
+            (Some(start_time), _) if time < start_time => FarmStatus::Upcoming,
+            (_, Some(end_time)) if time >= end_time => FarmStatus::Ended,
+            _ if self.paused => FarmStatus::Paused,
+            _ => FarmStatus::Active,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
@@ -37,6 +236,40 @@ pub struct State {
     pub last_distributed: u64,
     pub total_bond_amount: Uint256,
     pub global_reward_index: Decimal256,
+    #[serde(default)]
+    pub total_weight: Uint256,
+    #[serde(default)]
+    pub staker_count: u64,
+    #[serde(default)]
+    pub cumulative_reward_distributed: Uint256,
+    #[serde(default)]
+    pub cumulative_reward_claimed: Uint256,
+    #[serde(default)]
+    pub cumulative_bond_amount: Uint256,
+    #[serde(default)]
+    pub cumulative_unbond_amount: Uint256,
+    #[serde(default)]
+    pub reward_remainder: Decimal256,
+    #[serde(default)]
+    pub undistributed_amount: Uint256,
+    #[serde(default)]
+    pub vesting_amount: Uint256,
+    // deposited rewards left to stream until the end time
+    #[serde(default)]
+    pub reward_stream_amount: Uint256,
+    #[serde(default)]
+    pub reward_stream_end_time: u64,
+    // streamed rewards per second until the end time
+    #[serde(default)]
+    pub reward_rate: Decimal256,
+    // positions currently minted, and the last token ID ever minted
+    #[serde(default)]
+    pub position_count: u64,
+    #[serde(default)]
+    pub last_position_id: u64,
+    // referral rewards funded on top of the payouts of the referred stakers
+    #[serde(default)]
+    pub referral_reserve: Uint256,
 }
 
 impl State {
@@ -44,7 +277,17 @@ impl State {
         StateResponse {
             last_distributed: self.last_distributed,
             total_bond_amount: self.total_bond_amount,
+            total_weight: self.total_weight,
             global_reward_index: self.global_reward_index,
+            staker_count: self.staker_count,
+            cumulative_reward_distributed: self.cumulative_reward_distributed,
+            cumulative_reward_claimed: self.cumulative_reward_claimed,
+            cumulative_bond_amount: self.cumulative_bond_amount,
+            cumulative_unbond_amount: self.cumulative_unbond_amount,
+            reward_stream_amount: self.reward_stream_amount,
+            reward_stream_end_time: self.reward_stream_end_time,
+            reward_rate: self.reward_rate,
+            referral_reserve: self.referral_reserve,
         }
     }
 }
@@ -54,45 +297,349 @@ pub struct StakerInfo {
     pub reward_index: Decimal256,
     pub bond_amount: Uint256,
     pub pending_reward: Uint256,
+    #[serde(default)]
+    pub weight: Uint256,
+    // the fraction of a unit of reward not yet added to the pending reward
+    #[serde(default)]
+    pub reward_dust: Decimal256,
 }
 
 impl StakerInfo {
-    pub fn as_res(&self, staker: &Addr) -> StakerInfoResponse {
+    pub fn as_res(&self, staker: &Addr, reward_fee_rate: Decimal256) -> StakerInfoResponse {
         StakerInfoResponse {
             staker: staker.to_string(),
             reward_index: self.reward_index,
             bond_amount: self.bond_amount,
             pending_reward: self.pending_reward,
+            weight: self.weight,
+            pending_fee: self.pending_reward * reward_fee_rate,
+            rewarder_rewards: None,
         }
     }
 }
 
-const MAX_LIMIT: u32 = 30;
-const DEFAULT_LIMIT: u32 = 10;
+// bond held by a cw721 token, it earns rewards like the bond of a staker and
+// moves with the token along with its pending reward
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Position {
+    pub owner: CanonicalAddr,
+    pub bond_time: u64,
+    pub staker_info: StakerInfo,
+    // cw721 spenders allowed to transfer the position, cleared on transfer
+    #[serde(default)]
+    pub approvals: Vec<PositionApproval>,
+}
+
+impl Position {
+    pub fn approvals_res(
+        &self,
+        api: &dyn Api,
+        block: &BlockInfo,
+        include_expired: bool,
+    ) -> StdResult<Vec<NftApproval>> {
+        self.approvals
+            .iter()
+            .filter(|approval| include_expired || !approval.expires.is_expired(block))
+            .map(|approval| {
+                Ok(NftApproval {
+                    spender: api.addr_humanize(&approval.spender)?.to_string(),
+                    expires: approval.expires,
+                })
+            })
+            .collect::<StdResult<Vec<NftApproval>>>()
+    }
+
+    pub fn as_res(&self, reward_fee_rate: Decimal256) -> PositionResponse {
+        PositionResponse {
+            bond_amount: self.staker_info.bond_amount,
+            bond_time: self.bond_time,
+            reward_index: self.staker_info.reward_index,
+            pending_reward: self.staker_info.pending_reward,
+            weight: self.staker_info.weight,
+            pending_fee: self.staker_info.pending_reward * reward_fee_rate,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PositionApproval {
+    pub spender: CanonicalAddr,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Hook {
+    // whether a failing hook reverts the staking action
+    pub revert_on_failure: bool,
+    // gas available to the hook, always set for the hooks allowed to fail
+    pub gas_limit: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingSchedule {
+    pub start_time: u64,
+    pub end_time: u64,
+    pub amount: Uint256,
+    pub withdrawn_amount: Uint256,
+}
+
+impl VestingSchedule {
+    // amount vested at the given time and not withdrawn yet
+    pub fn claimable_amount(&self, time: u64) -> Uint256 {
+        let vested_amount: Uint256 = if time >= self.end_time {
+            self.amount
+        } else {
+            self.amount.multiply_ratio(
+                time.saturating_sub(self.start_time),
+                self.end_time - self.start_time,
+            )
+        };
+
+        vested_amount - self.withdrawn_amount
+    }
+
+    pub fn as_res(&self) -> VestingScheduleResponse {
+        VestingScheduleResponse {
+            start_time: self.start_time,
+            end_time: self.end_time,
+            amount: self.amount,
+            withdrawn_amount: self.withdrawn_amount,
+        }
+    }
+}
+
+pub const MAX_LIMIT: u32 = 30;
+pub const DEFAULT_LIMIT: u32 = 10;
+// hard caps of the reward, referral and deposit fee rates, in percent
+pub const MAX_REWARD_FEE_RATE: u64 = 20;
+pub const MAX_REFERRAL_RATE: u64 = 20;
+pub const MAX_DEPOSIT_FEE_RATE: u64 = 10;
+pub const MAX_HOOKS: usize = 10;
+// cw721 contract info of the positions
+pub const POSITION_NAME: &str = "MIAW LP Staking Position";
+pub const POSITION_SYMBOL: &str = "MIAW-LP-POS";
+
+// config layout of the contracts instantiated before the owner and page limits existed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
+    pub miaw_token: CanonicalAddr,
+    pub miaw_lp_token: CanonicalAddr,
+    pub distribution_schedule: Vec<ScheduleEntry>,
+}
+
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("\u{0}\u{6}config");
+
+pub fn read_allowlist(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    start_after: Option<CanonicalAddr>,
+    limit: usize,
+) -> StdResult<Vec<String>> {
+    let start: Option<Bound> = start_after.map(|addr| Bound::exclusive(addr.as_slice()));
 
+    ALLOWLIST
+        .keys(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|k| Ok(api.addr_humanize(&CanonicalAddr::from(k))?.to_string()))
+        .collect::<StdResult<Vec<String>>>()
+}
+
+pub fn read_hooks(storage: &dyn Storage, api: &dyn Api) -> StdResult<Vec<HookResponse>> {
+    HOOKS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (k, hook) = item?;
+            Ok(HookResponse {
+                addr: api.addr_humanize(&CanonicalAddr::from(k))?.to_string(),
+                revert_on_failure: hook.revert_on_failure,
+                gas_limit: hook.gas_limit,
+            })
+        })
+        .collect::<StdResult<Vec<HookResponse>>>()
+}
+
+pub fn read_referees(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    referrer: &CanonicalAddr,
+    start_after: Option<CanonicalAddr>,
+    limit: usize,
+) -> StdResult<Vec<String>> {
+    let start: Option<Bound> = start_after.map(|addr| Bound::exclusive(addr.as_slice()));
+
+    REFEREES
+        .prefix(referrer.as_slice())
+        .keys(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|k| Ok(api.addr_humanize(&CanonicalAddr::from(k))?.to_string()))
+        .collect::<StdResult<Vec<String>>>()
+}
+
+pub fn read_approvals(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    staker: &CanonicalAddr,
+    block: &BlockInfo,
+) -> StdResult<Vec<ApprovalResponse>> {
+    OPERATORS
+        .prefix(staker.as_slice())
+        .range(storage, None, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, expires)) => !expires.is_expired(block),
+            Err(_) => true,
+        })
+        .map(|item| {
+            let (k, expires) = item?;
+            Ok(ApprovalResponse {
+                operator: api.addr_humanize(&CanonicalAddr::from(k))?.to_string(),
+                expires,
+            })
+        })
+        .collect::<StdResult<Vec<ApprovalResponse>>>()
+}
+
+pub fn read_nft_operators(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    owner: &CanonicalAddr,
+    block: &BlockInfo,
+    include_expired: bool,
+    start_after: Option<CanonicalAddr>,
+    limit: usize,
+) -> StdResult<Vec<NftApproval>> {
+    let start: Option<Bound> = start_after.map(|addr| Bound::exclusive(addr.as_slice()));
+
+    NFT_OPERATORS
+        .prefix(owner.as_slice())
+        .range(storage, start, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, expires)) => include_expired || !expires.is_expired(block),
+            Err(_) => true,
+        })
+        .take(limit)
+        .map(|item| {
+            let (k, expires) = item?;
+            Ok(NftApproval {
+                spender: api.addr_humanize(&CanonicalAddr::from(k))?.to_string(),
+                expires,
+            })
+        })
+        .collect::<StdResult<Vec<NftApproval>>>()
+}
+
+pub fn read_position_ids(
+    storage: &dyn Storage,
+    owner: Option<&CanonicalAddr>,
+    start_after: Option<String>,
+    limit: usize,
+) -> StdResult<Vec<String>> {
+    let start: Option<Bound> = start_after.map(|token_id| Bound::exclusive(token_id.as_bytes()));
+
+    let keys: Vec<Vec<u8>> = match owner {
+        Some(owner) => positions()
+            .idx
+            .owner
+            .prefix(owner.to_vec())
+            .range(storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| Ok(item?.0))
+            .collect::<StdResult<Vec<Vec<u8>>>>()?,
+        None => positions()
+            .range(storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| Ok(item?.0))
+            .collect::<StdResult<Vec<Vec<u8>>>>()?,
+    };
+
+    keys.into_iter()
+        .map(|k| String::from_utf8(k).map_err(StdError::invalid_utf8))
+        .collect::<StdResult<Vec<String>>>()
+}
+
+// bond of the positions held by the owner
+pub fn read_owner_position_bond(
+    storage: &dyn Storage,
+    owner: &CanonicalAddr,
+) -> StdResult<Uint256> {
+    positions()
+        .idx
+        .owner
+        .prefix(owner.to_vec())
+        .range(storage, None, None, Order::Ascending)
+        .try_fold(Uint256::zero(), |total, item| {
+            Ok(total + item?.1.staker_info.bond_amount)
+        })
+}
+
+// bond of the owner and of the positions it holds
+pub fn read_owner_stake(storage: &dyn Storage, owner: &CanonicalAddr) -> StdResult<Uint256> {
+    let bond_amount: Uint256 = staker_infos()
+        .may_load(storage, owner.as_slice())?
+        .map(|staker_info| staker_info.bond_amount)
+        .unwrap_or_default();
+    Ok(bond_amount + read_owner_position_bond(storage, owner)?)
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn read_stakers_with_updated_reward(
     storage: &dyn Storage,
     api: &dyn Api,
     state: &State,
+    reward_fee_rate: Decimal256,
     start_after: Option<CanonicalAddr>,
-    limit: Option<u32>,
-    order_by: Option<OrderBy>,
-) -> StdResult<StakersInfoResponse> {
-    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    limit: usize,
+    order_by: OrderBy,
+) -> StdResult<Vec<StakerInfoResponse>> {
+    let cursor: Option<Bound> = start_after.map(|addr| Bound::exclusive(addr.as_slice()));
     let (start, end, order_by) = match order_by {
-        Some(OrderBy::Asc) => (
-            calc_range_start_addr(start_after).map(Bound::exclusive),
-            None,
-            Order::Ascending,
-        ),
-        _ => (
-            None,
-            calc_range_end_addr(start_after).map(Bound::exclusive),
+        OrderBy::Asc => (cursor, None, Order::Ascending),
+        OrderBy::Desc => (None, cursor, Order::Descending),
+    };
+
+    staker_infos()
+        .range(storage, start, end, order_by)
+        .take(limit)
+        .map(|item| {
+            let (k, mut staker) = item?;
+            let addr = api.addr_humanize(&CanonicalAddr::from(k))?;
+            compute_staker_reward(state, &mut staker);
+
+            Ok(staker.as_res(&addr, reward_fee_rate))
+        })
+        .collect::<StdResult<Vec<StakerInfoResponse>>>()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn read_stakers_by_bond_with_updated_reward(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    state: &State,
+    reward_fee_rate: Decimal256,
+    cursor: Option<Vec<u8>>,
+    limit: usize,
+    order_by: OrderBy,
+    min_bond: Option<Uint256>,
+) -> StdResult<Vec<StakerInfoResponse>> {
+    let min: Option<Vec<u8>> = min_bond.map(|amount| bond_index_key(&amount, &[]));
+    let (start, end, order_by) = match order_by {
+        OrderBy::Asc => {
+            let start = match (cursor, min) {
+                (Some(cursor), Some(min)) if cursor < min => Some(Bound::inclusive(min)),
+                (Some(cursor), _) => Some(Bound::exclusive(cursor)),
+                (None, min) => min.map(Bound::inclusive),
+            };
+            (start, None, Order::Ascending)
+        }
+        OrderBy::Desc => (
+            min.map(Bound::inclusive),
+            cursor.map(Bound::exclusive),
             Order::Descending,
         ),
     };
 
-    let stakers: Vec<StakerInfoResponse> = STAKER_INFO
+    staker_infos()
+        .idx
+        .bond_amount
         .range(storage, start, end, order_by)
         .take(limit)
         .map(|item| {
@@ -100,21 +647,23 @@ pub fn read_stakers_with_updated_reward(
             let addr = api.addr_humanize(&CanonicalAddr::from(k))?;
             compute_staker_reward(state, &mut staker);
 
-            Ok(staker.as_res(&addr))
+            Ok(staker.as_res(&addr, reward_fee_rate))
         })
-        .collect::<StdResult<Vec<StakerInfoResponse>>>()?;
+        .collect::<StdResult<Vec<StakerInfoResponse>>>()
+}
 
-    Ok(StakersInfoResponse { stakers })
+// opaque cursor of the bond amount order, the (bond_amount, address) index entry
+// of the last staker, so the page stays stable when that staker's bond changes
+pub fn encode_bond_cursor(amount: &Uint256, addr: &CanonicalAddr) -> String {
+    Binary::from(bond_index_key(amount, addr.as_slice())).to_base64()
 }
 
-fn calc_range_start_addr(start_after: Option<CanonicalAddr>) -> Option<Vec<u8>> {
-    start_after.map(|addr| {
-        let mut v = addr.as_slice().to_vec();
-        v.push(1);
-        v
-    })
+pub fn decode_bond_cursor(cursor: &str) -> Result<Vec<u8>, ContractError> {
+    Binary::from_base64(cursor)
+        .map(|cursor| cursor.to_vec())
+        .map_err(|_| ContractError::InvalidCursor {})
 }
 
-fn calc_range_end_addr(start_after: Option<CanonicalAddr>) -> Option<Vec<u8>> {
-    start_after.map(|addr| addr.as_slice().to_vec())
+fn bond_index_key(amount: &Uint256, pk: &[u8]) -> Vec<u8> {
+    (bond_amount_key(amount), pk.to_vec()).joined_key()
 }
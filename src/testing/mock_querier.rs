@@ -4,6 +4,10 @@ use cosmwasm_std::{
     OwnedDeps, Querier, QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
 };
 use cw20::{BalanceResponse as Cw20BalanceResponse, TokenInfoResponse};
+
+use crate::msg::{
+    RewarderPendingRewardsResponse, RewarderReward, StakedBalanceResponse, TotalStakedResponse,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -27,6 +31,7 @@ pub struct WasmMockQuerier {
     base: MockQuerier<Empty>,
     balance_querier: BalanceQuerier,
     terraswap_factory_querier: TerraswapFactoryQuerier,
+    rewarder_rewards: HashMap<String, Vec<RewarderReward>>,
 }
 
 #[derive(Clone, Default)]
@@ -99,6 +104,9 @@ impl Querier for WasmMockQuerier {
 pub enum MockQueryMsg {
     TokenInfo {},
     Balance { address: String },
+    PendingRewards { staker: String },
+    StakedBalance { address: String },
+    TotalStaked {},
 }
 
 impl WasmMockQuerier {
@@ -159,6 +167,47 @@ impl WasmMockQuerier {
                         let res = Cw20BalanceResponse { balance: *balance };
                         SystemResult::Ok(ContractResult::from(to_binary(&res)))
                     }
+                    MockQueryMsg::TotalStaked {} => {
+                        let total: Uint128 = match self.balance_querier.balances.get(contract_addr)
+                        {
+                            Some(balances) => balances.values().sum(),
+                            None => Uint128::zero(),
+                        };
+                        let res = TotalStakedResponse {
+                            total: total.into(),
+                        };
+                        SystemResult::Ok(ContractResult::from(to_binary(&res)))
+                    }
+                    MockQueryMsg::StakedBalance { address } => {
+                        let balance: Uint128 = self
+                            .balance_querier
+                            .balances
+                            .get(contract_addr)
+                            .and_then(|balances| balances.get(&address))
+                            .cloned()
+                            .unwrap_or_default();
+                        let res = StakedBalanceResponse {
+                            balance: balance.into(),
+                        };
+                        SystemResult::Ok(ContractResult::from(to_binary(&res)))
+                    }
+                    MockQueryMsg::PendingRewards { staker } => {
+                        let rewards: Vec<RewarderReward> = match self.rewarder_rewards.get(&staker)
+                        {
+                            Some(rewards) => rewards.clone(),
+                            None => {
+                                return SystemResult::Err(SystemError::InvalidRequest {
+                                    error: format!(
+                                        "No pending rewards exist for the staker {}",
+                                        staker
+                                    ),
+                                    request: msg.as_slice().into(),
+                                })
+                            }
+                        };
+                        let res = RewarderPendingRewardsResponse { rewards };
+                        SystemResult::Ok(ContractResult::from(to_binary(&res)))
+                    }
                 }
             }
             QueryRequest::Bank(BankQuery::Balance { address, denom }) => {
@@ -204,6 +253,7 @@ impl WasmMockQuerier {
             base,
             balance_querier: BalanceQuerier::default(),
             terraswap_factory_querier: TerraswapFactoryQuerier::default(),
+            rewarder_rewards: HashMap::new(),
         }
     }
 
@@ -212,6 +262,14 @@ impl WasmMockQuerier {
         self.terraswap_factory_querier = TerraswapFactoryQuerier::new(pairs);
     }
 
+    // configure the pending rewards of the secondary rewarder
+    pub fn with_rewarder_rewards(&mut self, rewards: &[(&String, Vec<RewarderReward>)]) {
+        for (staker, rewards) in rewards.iter() {
+            self.rewarder_rewards
+                .insert(staker.to_string(), rewards.clone());
+        }
+    }
+
     pub fn with_balances(&mut self, balances: &[(&String, &[(&String, &Uint128)])]) {
         self.balance_querier = BalanceQuerier::new(balances);
     }
@@ -1,14 +1,32 @@
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, migrate, query, reply};
+use crate::events::{
+    EVENT_ADD_HOOK, EVENT_APPROVE_OPERATOR, EVENT_BOND, EVENT_BOND_POSITION,
+    EVENT_CLAIM_POSITION_REWARDS, EVENT_CLAIM_REWARDS, EVENT_DISTRIBUTE, EVENT_UPDATE_CONFIG,
+};
+use crate::state::{State, STATE};
 use crate::testing::mock_querier::mock_dependencies;
 use crate::ContractError;
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{attr, from_binary, to_binary, CosmosMsg, SubMsg, Uint128, WasmMsg};
-use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cosmwasm_std::{
+    attr, from_binary, from_slice, to_binary, BankMsg, Coin, ContractResult, CosmosMsg, Deps, Env,
+    Event, Reply, SubMsg, Timestamp, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, Expiration};
+use terraswap::asset::AssetInfo;
 
 use crate::msg::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, OrderBy, QueryMsg, StakerInfoResponse,
-    StakersInfoResponse, StateResponse,
+    AllNftInfoResponse, AllowlistResponse, ApprovalResponse, ApprovalsResponse, BondRestrictions,
+    BoostConfig, BoostSource, ConfigResponse, ContractInfoResponse, Cw20HookMsg, Cw721ReceiveMsg,
+    Cw721ReceiverExecuteMsg, EmissionCurve, EmissionCurveResponse, EmissionPointResponse,
+    ExecuteMsg, FarmStatus, FarmWindow, HookResponse, HooksResponse, InstantiateMsg, MigrateMsg,
+    NftApproval, NftApprovalResponse, NftApprovalsResponse, NftInfoResponse, NumTokensResponse,
+    OperatorsResponse, OrderBy, OwnerOfResponse, PageLimits, PositionResponse, QueryMsg,
+    RefereesResponse, ReferralEarningsResponse, RewardDustResponse, RewardFees, RewarderExecuteMsg,
+    RewarderReward, ScheduleEntry, SimulationResponse, StakeChangedExecuteMsg, StakeChangedHookMsg,
+    StakerInfoResponse, StakersInfoResponse, StakersSortBy, StateResponse, StatusResponse,
+    TokensResponse, UndistributedPolicy, UndistributedRewardResponse, UpdateConfigMsg,
+    VestingConfig, VestingResponse, VestingScheduleResponse,
 };
 
 #[test]
@@ -19,7 +37,7 @@ fn proper_initialization() {
     let msg = InstantiateMsg {
         miaw_token: "miaw0000".to_string(),
         miaw_lp_token: "miawlp0000".to_string(),
-        distribution_schedule: vec![(100, 200, Uint256::from(1000000u128))],
+        distribution_schedule: vec![ScheduleEntry::linear(100, 200, Uint256::from(1000000u128))],
     };
 
     let info = mock_info("addr0000", &[]);
@@ -33,9 +51,29 @@ fn proper_initialization() {
     assert_eq!(
         config,
         ConfigResponse {
+            owner: "addr0000".to_string(),
             miaw_token: "miaw0000".to_string(),
             miaw_lp_token: "miawlp0000".to_string(),
-            distribution_schedule: vec![(100, 200, Uint256::from(1000000u128))],
+            distribution_schedule: vec![ScheduleEntry::linear(
+                100,
+                200,
+                Uint256::from(1000000u128)
+            )],
+            page_limits: PageLimits {
+                max_limit: 30,
+                default_limit: 10,
+            },
+            undistributed_policy: UndistributedPolicy::Reclaim,
+            vesting: VestingConfig::default(),
+            boost: BoostConfig::default(),
+            reward_fees: RewardFees::default(),
+            bond_restrictions: BondRestrictions::default(),
+            farm_window: FarmWindow::default(),
+            paused: false,
+            receipt_token: None,
+            rewarder: None,
+            reward_deposit_duration: 0,
+            keeper: None,
         }
     );
 
@@ -46,7 +84,17 @@ fn proper_initialization() {
         StateResponse {
             last_distributed: default_genesis_seconds,
             total_bond_amount: Uint256::zero(),
+            total_weight: Uint256::zero(),
             global_reward_index: Decimal256::zero(),
+            staker_count: 0,
+            cumulative_reward_distributed: Uint256::zero(),
+            cumulative_reward_claimed: Uint256::zero(),
+            cumulative_bond_amount: Uint256::zero(),
+            cumulative_unbond_amount: Uint256::zero(),
+            reward_stream_amount: Uint256::zero(),
+            reward_stream_end_time: 0,
+            reward_rate: Decimal256::zero(),
+            referral_reserve: Uint256::zero(),
         }
     );
 }
@@ -60,12 +108,12 @@ fn test_bond_tokens() {
         miaw_token: "miaw0000".to_string(),
         miaw_lp_token: "miawlp0000".to_string(),
         distribution_schedule: vec![
-            (
+            ScheduleEntry::linear(
                 default_genesis_seconds,
                 default_genesis_seconds + 100,
                 Uint256::from(1000000u128),
             ),
-            (
+            ScheduleEntry::linear(
                 default_genesis_seconds + 100,
                 default_genesis_seconds + 200,
                 Uint256::from(10000000u128),
@@ -79,7 +127,7 @@ fn test_bond_tokens() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
     });
 
     let info = mock_info("miawlp0000", &[]);
@@ -103,6 +151,9 @@ fn test_bond_tokens() {
             reward_index: Decimal256::zero(),
             pending_reward: Uint256::zero(),
             bond_amount: Uint256::from(100u128),
+            weight: Uint256::from(100u128),
+            pending_fee: Uint256::zero(),
+            rewarder_rewards: None,
         }
     );
 
@@ -113,8 +164,18 @@ fn test_bond_tokens() {
         .unwrap(),
         StateResponse {
             total_bond_amount: Uint256::from(100u128),
+            total_weight: Uint256::from(100u128),
             global_reward_index: Decimal256::zero(),
             last_distributed: default_genesis_seconds,
+            staker_count: 1,
+            cumulative_reward_distributed: Uint256::zero(),
+            cumulative_reward_claimed: Uint256::zero(),
+            cumulative_bond_amount: Uint256::from(100u128),
+            cumulative_unbond_amount: Uint256::zero(),
+            reward_stream_amount: Uint256::zero(),
+            reward_stream_end_time: 0,
+            reward_rate: Decimal256::zero(),
+            referral_reserve: Uint256::zero(),
         }
     );
 
@@ -122,7 +183,7 @@ fn test_bond_tokens() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
     });
     env.block.time = env.block.time.plus_seconds(10);
 
@@ -145,6 +206,9 @@ fn test_bond_tokens() {
             reward_index: Decimal256::from_ratio(1000, 1),
             pending_reward: Uint256::from(100000u128),
             bond_amount: Uint256::from(200u128),
+            weight: Uint256::from(200u128),
+            pending_fee: Uint256::zero(),
+            rewarder_rewards: None,
         }
     );
 
@@ -153,8 +217,18 @@ fn test_bond_tokens() {
             .unwrap(),
         StateResponse {
             total_bond_amount: Uint256::from(200u128),
+            total_weight: Uint256::from(200u128),
             global_reward_index: Decimal256::from_ratio(1000, 1),
             last_distributed: default_genesis_seconds + 10,
+            staker_count: 1,
+            cumulative_reward_distributed: Uint256::from(100000u128),
+            cumulative_reward_claimed: Uint256::zero(),
+            cumulative_bond_amount: Uint256::from(200u128),
+            cumulative_unbond_amount: Uint256::zero(),
+            reward_stream_amount: Uint256::zero(),
+            reward_stream_end_time: 0,
+            reward_rate: Decimal256::zero(),
+            referral_reserve: Uint256::zero(),
         }
     );
 
@@ -162,7 +236,7 @@ fn test_bond_tokens() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
     });
 
     let info = mock_info("staking0001", &[]);
@@ -179,12 +253,12 @@ fn test_unbond() {
         miaw_token: "miaw0000".to_string(),
         miaw_lp_token: "miawlp0000".to_string(),
         distribution_schedule: vec![
-            (
+            ScheduleEntry::linear(
                 default_genesis_seconds,
                 default_genesis_seconds + 100,
                 Uint256::from(1000000u128),
             ),
-            (
+            ScheduleEntry::linear(
                 default_genesis_seconds + 100,
                 default_genesis_seconds + 200,
                 Uint256::from(10000000u128),
@@ -199,7 +273,7 @@ fn test_unbond() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
     });
     let info = mock_info("miawlp0000", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -207,6 +281,8 @@ fn test_unbond() {
     // unbond 150 tokens; failed
     let msg = ExecuteMsg::Unbond {
         amount: Some(Uint256::from(150u128)),
+        staker: None,
+        token_id: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -216,6 +292,8 @@ fn test_unbond() {
     // normal unbond
     let msg = ExecuteMsg::Unbond {
         amount: Some(Uint256::from(60u128)),
+        staker: None,
+        token_id: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -233,7 +311,11 @@ fn test_unbond() {
     );
 
     // unbond remaining
-    let msg = ExecuteMsg::Unbond { amount: None };
+    let msg = ExecuteMsg::Unbond {
+        amount: None,
+        staker: None,
+        token_id: None,
+    };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(
@@ -248,6 +330,27 @@ fn test_unbond() {
             funds: vec![],
         }))]
     );
+    assert_eq!(
+        from_binary::<StateResponse>(
+            &query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap()
+        )
+        .unwrap(),
+        StateResponse {
+            total_bond_amount: Uint256::zero(),
+            total_weight: Uint256::zero(),
+            global_reward_index: Decimal256::zero(),
+            last_distributed: default_genesis_seconds,
+            staker_count: 0,
+            cumulative_reward_distributed: Uint256::zero(),
+            cumulative_reward_claimed: Uint256::zero(),
+            cumulative_bond_amount: Uint256::from(100u128),
+            cumulative_unbond_amount: Uint256::from(100u128),
+            reward_stream_amount: Uint256::zero(),
+            reward_stream_end_time: 0,
+            reward_rate: Decimal256::zero(),
+            referral_reserve: Uint256::zero(),
+        }
+    );
 }
 
 #[test]
@@ -259,12 +362,12 @@ fn test_compute_reward() {
         miaw_token: "miaw0000".to_string(),
         miaw_lp_token: "miawlp0000".to_string(),
         distribution_schedule: vec![
-            (
+            ScheduleEntry::linear(
                 default_genesis_seconds,
                 default_genesis_seconds + 100,
                 Uint256::from(1000000u128),
             ),
-            (
+            ScheduleEntry::linear(
                 default_genesis_seconds + 100,
                 default_genesis_seconds + 200,
                 Uint256::from(2000000u128),
@@ -279,7 +382,7 @@ fn test_compute_reward() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
     });
     let info = mock_info("miawlp0000", &[]);
     let mut env = mock_env();
@@ -293,7 +396,7 @@ fn test_compute_reward() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
     });
     let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
@@ -314,6 +417,9 @@ fn test_compute_reward() {
             reward_index: Decimal256::from_ratio(1000, 1),
             pending_reward: Uint256::from(100000u128),
             bond_amount: Uint256::from(200u128),
+            weight: Uint256::from(200u128),
+            pending_fee: Uint256::zero(),
+            rewarder_rewards: None,
         }
     );
 
@@ -325,6 +431,8 @@ fn test_compute_reward() {
     // unbond
     let msg = ExecuteMsg::Unbond {
         amount: Some(Uint256::from(100u128)),
+        staker: None,
+        token_id: None,
     };
     let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
     assert_eq!(
@@ -344,6 +452,9 @@ fn test_compute_reward() {
             reward_index: Decimal256::from_ratio(6500, 1), // 1,000 + 5,500
             pending_reward: Uint256::from(1200000u128),    // 0.1 + 1.1
             bond_amount: Uint256::from(100u128),
+            weight: Uint256::from(100u128),
+            pending_fee: Uint256::zero(),
+            rewarder_rewards: None,
         }
     );
 }
@@ -357,12 +468,12 @@ fn test_claim_rewards() {
         miaw_token: "miaw0000".to_string(),
         miaw_lp_token: "miawlp0000".to_string(),
         distribution_schedule: vec![
-            (
+            ScheduleEntry::linear(
                 default_genesis_seconds,
                 default_genesis_seconds + 100,
                 Uint256::from(1000000u128),
             ),
-            (
+            ScheduleEntry::linear(
                 default_genesis_seconds + 100,
                 default_genesis_seconds + 200,
                 Uint256::from(10000000u128),
@@ -377,7 +488,7 @@ fn test_claim_rewards() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
     });
     let info = mock_info("miawlp0000", &[]);
     let mut env = mock_env();
@@ -388,8 +499,11 @@ fn test_claim_rewards() {
     env.block.time = env.block.time.plus_seconds(100);
     let info = mock_info("addr0000", &[]);
 
-    let msg = ExecuteMsg::ClaimRewards {};
-    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    let msg = ExecuteMsg::ClaimRewards {
+        staker: None,
+        token_id: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
     assert_eq!(
         res.messages,
@@ -403,6 +517,25 @@ fn test_claim_rewards() {
             funds: vec![],
         }))]
     );
+    assert_eq!(
+        from_binary::<StateResponse>(&query(deps.as_ref(), env, QueryMsg::State {}).unwrap())
+            .unwrap(),
+        StateResponse {
+            total_bond_amount: Uint256::from(100u128),
+            total_weight: Uint256::from(100u128),
+            global_reward_index: Decimal256::from_ratio(10000, 1),
+            last_distributed: default_genesis_seconds + 100,
+            staker_count: 1,
+            cumulative_reward_distributed: Uint256::from(1000000u128),
+            cumulative_reward_claimed: Uint256::from(1000000u128),
+            cumulative_bond_amount: Uint256::from(100u128),
+            cumulative_unbond_amount: Uint256::zero(),
+            reward_stream_amount: Uint256::zero(),
+            reward_stream_end_time: 0,
+            reward_rate: Decimal256::zero(),
+            referral_reserve: Uint256::zero(),
+        }
+    );
 }
 
 #[test]
@@ -414,12 +547,12 @@ fn test_bond_hook() {
         miaw_token: "miaw0000".to_string(),
         miaw_lp_token: "miawlp0000".to_string(),
         distribution_schedule: vec![
-            (
+            ScheduleEntry::linear(
                 default_genesis_seconds,
                 default_genesis_seconds + 100,
                 Uint256::from(1000000u128),
             ),
-            (
+            ScheduleEntry::linear(
                 default_genesis_seconds + 100,
                 default_genesis_seconds + 200,
                 Uint256::from(10000000u128),
@@ -434,7 +567,7 @@ fn test_bond_hook() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
     });
     let info = mock_info("miawlp0000", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -456,6 +589,7 @@ fn test_bond_hook() {
             attr("action", "bond_hook"),
             attr("owner", "addr0000"),
             attr("amount", "300"),
+            attr("deposit_fee", "0"),
         ]
     );
     assert!(res.messages.is_empty());
@@ -469,7 +603,7 @@ fn test_query_stakers() {
     let msg = InstantiateMsg {
         miaw_token: "miaw0000".to_string(),
         miaw_lp_token: "miawlp0000".to_string(),
-        distribution_schedule: vec![(
+        distribution_schedule: vec![ScheduleEntry::linear(
             default_genesis_seconds,
             default_genesis_seconds + 100,
             Uint256::from(1000000u128),
@@ -481,7 +615,7 @@ fn test_query_stakers() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
     });
 
     let info = mock_info("miawlp0000", &[]);
@@ -491,14 +625,14 @@ fn test_query_stakers() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0001".to_string(),
         amount: Uint128::from(200u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
     });
     execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0002".to_string(),
         amount: Uint128::from(300u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
     });
     execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
@@ -511,6 +645,8 @@ fn test_query_stakers() {
                     start_after: None,
                     limit: None,
                     order_by: Some(OrderBy::Asc),
+                    sort_by: None,
+                    min_bond: None,
                 },
             )
             .unwrap(),
@@ -523,20 +659,31 @@ fn test_query_stakers() {
                     reward_index: Decimal256::zero(),
                     pending_reward: Uint256::zero(),
                     bond_amount: Uint256::from(100u128),
+                    weight: Uint256::from(100u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
                 },
                 StakerInfoResponse {
                     staker: "addr0001".to_string(),
                     reward_index: Decimal256::zero(),
                     pending_reward: Uint256::zero(),
                     bond_amount: Uint256::from(200u128),
+                    weight: Uint256::from(200u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
                 },
                 StakerInfoResponse {
                     staker: "addr0002".to_string(),
                     reward_index: Decimal256::zero(),
                     pending_reward: Uint256::zero(),
                     bond_amount: Uint256::from(300u128),
+                    weight: Uint256::from(300u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
                 },
-            ]
+            ],
+            total_count: Some(3),
+            next_cursor: None,
         }
     );
     assert_eq!(
@@ -548,6 +695,8 @@ fn test_query_stakers() {
                     start_after: None,
                     limit: None,
                     order_by: None,
+                    sort_by: None,
+                    min_bond: None,
                 },
             )
             .unwrap(),
@@ -560,20 +709,31 @@ fn test_query_stakers() {
                     reward_index: Decimal256::zero(),
                     pending_reward: Uint256::zero(),
                     bond_amount: Uint256::from(300u128),
+                    weight: Uint256::from(300u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
                 },
                 StakerInfoResponse {
                     staker: "addr0001".to_string(),
                     reward_index: Decimal256::zero(),
                     pending_reward: Uint256::zero(),
                     bond_amount: Uint256::from(200u128),
+                    weight: Uint256::from(200u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
                 },
                 StakerInfoResponse {
                     staker: "addr0000".to_string(),
                     reward_index: Decimal256::zero(),
                     pending_reward: Uint256::zero(),
                     bond_amount: Uint256::from(100u128),
+                    weight: Uint256::from(100u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
                 },
-            ]
+            ],
+            total_count: Some(3),
+            next_cursor: None,
         }
     );
     assert_eq!(
@@ -585,6 +745,8 @@ fn test_query_stakers() {
                     start_after: Some("addr0002".to_string()),
                     limit: Some(1u32),
                     order_by: None,
+                    sort_by: None,
+                    min_bond: None,
                 },
             )
             .unwrap(),
@@ -596,7 +758,12 @@ fn test_query_stakers() {
                 reward_index: Decimal256::zero(),
                 pending_reward: Uint256::zero(),
                 bond_amount: Uint256::from(200u128),
-            },]
+                weight: Uint256::from(200u128),
+                pending_fee: Uint256::zero(),
+                rewarder_rewards: None,
+            },],
+            total_count: Some(3),
+            next_cursor: Some("addr0001".to_string()),
         }
     );
     assert_eq!(
@@ -608,6 +775,8 @@ fn test_query_stakers() {
                     start_after: Some("addr0001".to_string()),
                     limit: Some(1u32),
                     order_by: Some(OrderBy::Asc),
+                    sort_by: None,
+                    min_bond: None,
                 },
             )
             .unwrap(),
@@ -619,7 +788,4718 @@ fn test_query_stakers() {
                 reward_index: Decimal256::zero(),
                 pending_reward: Uint256::zero(),
                 bond_amount: Uint256::from(300u128),
-            },]
+                weight: Uint256::from(300u128),
+                pending_fee: Uint256::zero(),
+                rewarder_rewards: None,
+            },],
+            total_count: Some(3),
+            next_cursor: Some("addr0002".to_string()),
+        }
+    );
+
+    // malformed cursor
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::StakersInfo {
+            start_after: Some("A".to_string()),
+            limit: None,
+            order_by: None,
+            sort_by: None,
+            min_bond: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidCursor {});
+}
+
+#[test]
+fn test_update_config() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(100, 200, Uint256::from(1000000u128))],
+    };
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // failed with unauthorized
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        page_limits: Some(PageLimits {
+            max_limit: 50u32,
+            default_limit: 10u32,
+        }),
+        ..UpdateConfigMsg::default()
+    });
+    let info = mock_info("addr0001", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // default limit above max limit
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        page_limits: Some(PageLimits {
+            max_limit: 5u32,
+            default_limit: 10u32,
+        }),
+        ..UpdateConfigMsg::default()
+    });
+    let info = mock_info("addr0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidPageLimits {});
+
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        owner: Some("addr0001".to_string()),
+        page_limits: Some(PageLimits {
+            max_limit: 50u32,
+            default_limit: 20u32,
+        }),
+        undistributed_policy: Some(UndistributedPolicy::CarryForward),
+        ..UpdateConfigMsg::default()
+    });
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(res.attributes, vec![attr("action", "update_config")]);
+
+    assert_eq!(
+        from_binary::<ConfigResponse>(
+            &query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()
+        )
+        .unwrap(),
+        ConfigResponse {
+            owner: "addr0001".to_string(),
+            miaw_token: "miaw0000".to_string(),
+            miaw_lp_token: "miawlp0000".to_string(),
+            distribution_schedule: vec![ScheduleEntry::linear(
+                100,
+                200,
+                Uint256::from(1000000u128)
+            )],
+            page_limits: PageLimits {
+                max_limit: 50,
+                default_limit: 20,
+            },
+            undistributed_policy: UndistributedPolicy::CarryForward,
+            vesting: VestingConfig::default(),
+            boost: BoostConfig::default(),
+            reward_fees: RewardFees::default(),
+            bond_restrictions: BondRestrictions::default(),
+            farm_window: FarmWindow::default(),
+            paused: false,
+            receipt_token: None,
+            rewarder: None,
+            reward_deposit_duration: 0,
+            keeper: None,
+        }
+    );
+}
+
+#[test]
+fn test_simulate() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    let info = mock_info("miawlp0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // 10 seconds later, bond 100 more tokens
+    // 100,000 rewards distributed
+    assert_eq!(
+        from_binary::<SimulationResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::SimulateBond {
+                    staker: "addr0000".to_string(),
+                    amount: Uint256::from(100u128),
+                    time: Some(default_genesis_seconds + 10),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap(),
+        SimulationResponse {
+            staker_info: StakerInfoResponse {
+                staker: "addr0000".to_string(),
+                reward_index: Decimal256::from_ratio(1000, 1),
+                pending_reward: Uint256::from(100000u128),
+                bond_amount: Uint256::from(200u128),
+                weight: Uint256::from(200u128),
+                pending_fee: Uint256::zero(),
+                rewarder_rewards: None,
+            },
+            state: StateResponse {
+                total_bond_amount: Uint256::from(200u128),
+                total_weight: Uint256::from(200u128),
+                global_reward_index: Decimal256::from_ratio(1000, 1),
+                last_distributed: default_genesis_seconds + 10,
+                staker_count: 1,
+                cumulative_reward_distributed: Uint256::from(100000u128),
+                cumulative_reward_claimed: Uint256::zero(),
+                cumulative_bond_amount: Uint256::from(200u128),
+                cumulative_unbond_amount: Uint256::zero(),
+                reward_stream_amount: Uint256::zero(),
+                reward_stream_end_time: 0,
+                reward_rate: Decimal256::zero(),
+                referral_reserve: Uint256::zero(),
+            },
+            reward_amount: Uint256::zero(),
+            fee_amount: Uint256::zero(),
+            referral_amount: Uint256::zero(),
+        }
+    );
+
+    // unbond everything at the end of the schedule
+    assert_eq!(
+        from_binary::<SimulationResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::SimulateUnbond {
+                    staker: "addr0000".to_string(),
+                    amount: None,
+                    time: Some(default_genesis_seconds + 100),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap(),
+        SimulationResponse {
+            staker_info: StakerInfoResponse {
+                staker: "addr0000".to_string(),
+                reward_index: Decimal256::from_ratio(10000, 1),
+                pending_reward: Uint256::from(1000000u128),
+                bond_amount: Uint256::zero(),
+                weight: Uint256::zero(),
+                pending_fee: Uint256::zero(),
+                rewarder_rewards: None,
+            },
+            state: StateResponse {
+                total_bond_amount: Uint256::zero(),
+                total_weight: Uint256::zero(),
+                global_reward_index: Decimal256::from_ratio(10000, 1),
+                last_distributed: default_genesis_seconds + 100,
+                staker_count: 1,
+                cumulative_reward_distributed: Uint256::from(1000000u128),
+                cumulative_reward_claimed: Uint256::zero(),
+                cumulative_bond_amount: Uint256::from(100u128),
+                cumulative_unbond_amount: Uint256::from(100u128),
+                reward_stream_amount: Uint256::zero(),
+                reward_stream_end_time: 0,
+                reward_rate: Decimal256::zero(),
+                referral_reserve: Uint256::zero(),
+            },
+            reward_amount: Uint256::zero(),
+            fee_amount: Uint256::zero(),
+            referral_amount: Uint256::zero(),
+        }
+    );
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::SimulateUnbond {
+            staker: "addr0000".to_string(),
+            amount: Some(Uint256::from(150u128)),
+            time: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidUnbondAmount {});
+
+    // claim after 50 seconds
+    assert_eq!(
+        from_binary::<SimulationResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::SimulateClaim {
+                    staker: "addr0000".to_string(),
+                    time: Some(default_genesis_seconds + 50),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap(),
+        SimulationResponse {
+            staker_info: StakerInfoResponse {
+                staker: "addr0000".to_string(),
+                reward_index: Decimal256::from_ratio(5000, 1),
+                pending_reward: Uint256::zero(),
+                bond_amount: Uint256::from(100u128),
+                weight: Uint256::from(100u128),
+                pending_fee: Uint256::zero(),
+                rewarder_rewards: None,
+            },
+            state: StateResponse {
+                total_bond_amount: Uint256::from(100u128),
+                total_weight: Uint256::from(100u128),
+                global_reward_index: Decimal256::from_ratio(5000, 1),
+                last_distributed: default_genesis_seconds + 50,
+                staker_count: 1,
+                cumulative_reward_distributed: Uint256::from(500000u128),
+                cumulative_reward_claimed: Uint256::from(500000u128),
+                cumulative_bond_amount: Uint256::from(100u128),
+                cumulative_unbond_amount: Uint256::zero(),
+                reward_stream_amount: Uint256::zero(),
+                reward_stream_end_time: 0,
+                reward_rate: Decimal256::zero(),
+                referral_reserve: Uint256::zero(),
+            },
+            reward_amount: Uint256::from(500000u128),
+            fee_amount: Uint256::zero(),
+            referral_amount: Uint256::zero(),
+        }
+    );
+
+    // simulating the past is not allowed
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::SimulateClaim {
+            staker: "addr0000".to_string(),
+            time: Some(default_genesis_seconds - 1),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidSimulationTime {});
+
+    // simulations do not touch the storage
+    assert_eq!(
+        from_binary::<StateResponse>(
+            &query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap()
+        )
+        .unwrap(),
+        StateResponse {
+            total_bond_amount: Uint256::from(100u128),
+            total_weight: Uint256::from(100u128),
+            global_reward_index: Decimal256::zero(),
+            last_distributed: default_genesis_seconds,
+            staker_count: 1,
+            cumulative_reward_distributed: Uint256::zero(),
+            cumulative_reward_claimed: Uint256::zero(),
+            cumulative_bond_amount: Uint256::from(100u128),
+            cumulative_unbond_amount: Uint256::zero(),
+            reward_stream_amount: Uint256::zero(),
+            reward_stream_end_time: 0,
+            reward_rate: Decimal256::zero(),
+            referral_reserve: Uint256::zero(),
+        }
+    );
+}
+
+#[test]
+fn test_query_unknown_stakers() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    let info = mock_info("miawlp0000", &[]);
+    let mut env = mock_env();
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // 10 seconds passed
+    // 100,000 rewards distributed
+    env.block.time = env.block.time.plus_seconds(10);
+
+    assert_eq!(
+        from_binary::<StakerInfoResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::StakerInfo {
+                    staker: "addr0001".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap(),
+        StakerInfoResponse {
+            staker: "addr0001".to_string(),
+            reward_index: Decimal256::from_ratio(1000, 1),
+            pending_reward: Uint256::zero(),
+            bond_amount: Uint256::zero(),
+            weight: Uint256::zero(),
+            pending_fee: Uint256::zero(),
+            rewarder_rewards: None,
+        }
+    );
+
+    assert_eq!(
+        from_binary::<StakersInfoResponse>(
+            &query(
+                deps.as_ref(),
+                env,
+                QueryMsg::StakersByAddress {
+                    addresses: vec!["addr0001".to_string(), "addr0000".to_string()],
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap(),
+        StakersInfoResponse {
+            stakers: vec![
+                StakerInfoResponse {
+                    staker: "addr0001".to_string(),
+                    reward_index: Decimal256::from_ratio(1000, 1),
+                    pending_reward: Uint256::zero(),
+                    bond_amount: Uint256::zero(),
+                    weight: Uint256::zero(),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                },
+                StakerInfoResponse {
+                    staker: "addr0000".to_string(),
+                    reward_index: Decimal256::from_ratio(1000, 1),
+                    pending_reward: Uint256::from(100000u128),
+                    bond_amount: Uint256::from(100u128),
+                    weight: Uint256::from(100u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                },
+            ],
+            total_count: Some(2),
+            next_cursor: None,
         }
     );
+
+    // failed with more addresses than the max limit
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::StakersByAddress {
+            addresses: (0..31).map(|i| format!("addr{:04}", i)).collect(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::TooManyAddresses { max: 30 });
+}
+
+#[test]
+fn test_query_stakers_by_bond_amount() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(300u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+
+    let info = mock_info("miawlp0000", &[]);
+    let env = mock_env();
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0002".to_string(),
+        amount: Uint128::from(200u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0003".to_string(),
+        amount: Uint128::from(50u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    assert_eq!(
+        from_binary::<StakersInfoResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::StakersInfo {
+                    start_after: None,
+                    limit: None,
+                    order_by: None,
+                    sort_by: Some(StakersSortBy::BondAmount),
+                    min_bond: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap(),
+        StakersInfoResponse {
+            stakers: vec![
+                StakerInfoResponse {
+                    staker: "addr0000".to_string(),
+                    reward_index: Decimal256::zero(),
+                    pending_reward: Uint256::zero(),
+                    bond_amount: Uint256::from(300u128),
+                    weight: Uint256::from(300u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                },
+                StakerInfoResponse {
+                    staker: "addr0002".to_string(),
+                    reward_index: Decimal256::zero(),
+                    pending_reward: Uint256::zero(),
+                    bond_amount: Uint256::from(200u128),
+                    weight: Uint256::from(200u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                },
+                StakerInfoResponse {
+                    staker: "addr0001".to_string(),
+                    reward_index: Decimal256::zero(),
+                    pending_reward: Uint256::zero(),
+                    bond_amount: Uint256::from(100u128),
+                    weight: Uint256::from(100u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                },
+                StakerInfoResponse {
+                    staker: "addr0003".to_string(),
+                    reward_index: Decimal256::zero(),
+                    pending_reward: Uint256::zero(),
+                    bond_amount: Uint256::from(50u128),
+                    weight: Uint256::from(50u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                },
+            ],
+            total_count: Some(4),
+            next_cursor: None,
+        }
+    );
+
+    // filter by min bond and page ascending
+    let res: StakersInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::StakersInfo {
+                start_after: None,
+                limit: Some(2u32),
+                order_by: Some(OrderBy::Asc),
+                sort_by: Some(StakersSortBy::BondAmount),
+                min_bond: Some(Uint256::from(100u128)),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let cursor: String = res.next_cursor.clone().unwrap();
+    assert_eq!(
+        res,
+        StakersInfoResponse {
+            stakers: vec![
+                StakerInfoResponse {
+                    staker: "addr0001".to_string(),
+                    reward_index: Decimal256::zero(),
+                    pending_reward: Uint256::zero(),
+                    bond_amount: Uint256::from(100u128),
+                    weight: Uint256::from(100u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                },
+                StakerInfoResponse {
+                    staker: "addr0002".to_string(),
+                    reward_index: Decimal256::zero(),
+                    pending_reward: Uint256::zero(),
+                    bond_amount: Uint256::from(200u128),
+                    weight: Uint256::from(200u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                },
+            ],
+            total_count: None,
+            next_cursor: Some(cursor.clone()),
+        }
+    );
+    assert_eq!(
+        from_binary::<StakersInfoResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::StakersInfo {
+                    start_after: Some(cursor.clone()),
+                    limit: Some(2u32),
+                    order_by: Some(OrderBy::Asc),
+                    sort_by: Some(StakersSortBy::BondAmount),
+                    min_bond: Some(Uint256::from(100u128)),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap(),
+        StakersInfoResponse {
+            stakers: vec![StakerInfoResponse {
+                staker: "addr0000".to_string(),
+                reward_index: Decimal256::zero(),
+                pending_reward: Uint256::zero(),
+                bond_amount: Uint256::from(300u128),
+                weight: Uint256::from(300u128),
+                pending_fee: Uint256::zero(),
+                rewarder_rewards: None,
+            },],
+            total_count: None,
+            next_cursor: None,
+        }
+    );
+
+    // page descending
+    assert_eq!(
+        from_binary::<StakersInfoResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::StakersInfo {
+                    start_after: Some(cursor.clone()),
+                    limit: None,
+                    order_by: Some(OrderBy::Desc),
+                    sort_by: Some(StakersSortBy::BondAmount),
+                    min_bond: Some(Uint256::from(100u128)),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap(),
+        StakersInfoResponse {
+            stakers: vec![StakerInfoResponse {
+                staker: "addr0001".to_string(),
+                reward_index: Decimal256::zero(),
+                pending_reward: Uint256::zero(),
+                bond_amount: Uint256::from(100u128),
+                weight: Uint256::from(100u128),
+                pending_fee: Uint256::zero(),
+                rewarder_rewards: None,
+            },],
+            total_count: None,
+            next_cursor: None,
+        }
+    );
+
+    // an address is not a bond amount cursor
+    let err = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::StakersInfo {
+            start_after: Some("addr 0002".to_string()),
+            limit: None,
+            order_by: None,
+            sort_by: Some(StakersSortBy::BondAmount),
+            min_bond: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidCursor {});
+
+    // the filtered stakers are only listed by bond amount
+    let err = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::StakersInfo {
+            start_after: None,
+            limit: None,
+            order_by: None,
+            sort_by: Some(StakersSortBy::Address),
+            min_bond: Some(Uint256::from(100u128)),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::MinBondRequiresBondOrder {});
+
+    let res: StakersInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::StakersInfo {
+                start_after: None,
+                limit: Some(3u32),
+                order_by: Some(OrderBy::Desc),
+                sort_by: Some(StakersSortBy::BondAmount),
+                min_bond: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.stakers
+            .iter()
+            .map(|staker| staker.staker.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["addr0000", "addr0002", "addr0001"]
+    );
+    let cursor: String = res.next_cursor.unwrap();
+
+    // index follows bond changes
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(250u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // the cursor still points after the bond the last staker had on the page
+    let res: StakersInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::StakersInfo {
+                start_after: Some(cursor),
+                limit: Some(3u32),
+                order_by: Some(OrderBy::Desc),
+                sort_by: Some(StakersSortBy::BondAmount),
+                min_bond: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.stakers
+            .iter()
+            .map(|staker| staker.staker.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["addr0003"]
+    );
+
+    let info = mock_info("addr0003", &[]);
+    let msg = ExecuteMsg::Unbond {
+        amount: None,
+        staker: None,
+        token_id: None,
+    };
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    assert_eq!(
+        from_binary::<StakersInfoResponse>(
+            &query(
+                deps.as_ref(),
+                env,
+                QueryMsg::StakersInfo {
+                    start_after: None,
+                    limit: None,
+                    order_by: None,
+                    sort_by: Some(StakersSortBy::BondAmount),
+                    min_bond: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap(),
+        StakersInfoResponse {
+            stakers: vec![
+                StakerInfoResponse {
+                    staker: "addr0001".to_string(),
+                    reward_index: Decimal256::zero(),
+                    pending_reward: Uint256::zero(),
+                    bond_amount: Uint256::from(350u128),
+                    weight: Uint256::from(350u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                },
+                StakerInfoResponse {
+                    staker: "addr0000".to_string(),
+                    reward_index: Decimal256::zero(),
+                    pending_reward: Uint256::zero(),
+                    bond_amount: Uint256::from(300u128),
+                    weight: Uint256::from(300u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                },
+                StakerInfoResponse {
+                    staker: "addr0002".to_string(),
+                    reward_index: Decimal256::zero(),
+                    pending_reward: Uint256::zero(),
+                    bond_amount: Uint256::from(200u128),
+                    weight: Uint256::from(200u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                },
+            ],
+            total_count: Some(3),
+            next_cursor: None,
+        }
+    );
+}
+
+#[test]
+fn test_reward_rounding_never_overpays() {
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+    let schedule_total = Uint256::from(1000003u128 + 7777777u128);
+    let stakers = ["addr0001", "addr0002", "addr0003"];
+
+    for seed in 1u64..=5u64 {
+        let mut deps = mock_dependencies(&[]);
+
+        // simple lcg, so the sequence is reproducible
+        let mut rng = seed;
+        let mut next = |max: u64| -> u64 {
+            rng = rng
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (rng >> 33) % max
+        };
+
+        let msg = InstantiateMsg {
+            miaw_token: "miaw0000".to_string(),
+            miaw_lp_token: "miawlp0000".to_string(),
+            distribution_schedule: vec![
+                ScheduleEntry::linear(
+                    default_genesis_seconds,
+                    default_genesis_seconds + 997,
+                    Uint256::from(1000003u128),
+                ),
+                ScheduleEntry::linear(
+                    default_genesis_seconds + 997,
+                    default_genesis_seconds + 2000,
+                    Uint256::from(7777777u128),
+                ),
+            ],
+        };
+        let info = mock_info("addr0000", &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // keep bonding open after the schedule ends
+        let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+            farm_window: Some(FarmWindow {
+                start_time: None,
+                end_time: Some(default_genesis_seconds + 10000),
+            }),
+            ..UpdateConfigMsg::default()
+        });
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // keep something bonded the whole time, so no emission is skipped
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr0000".to_string(),
+            amount: Uint128::from(7u128),
+            msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+        });
+        let lp_info = mock_info("miawlp0000", &[]);
+        let mut env = mock_env();
+        execute(deps.as_mut(), env.clone(), lp_info.clone(), msg).unwrap();
+
+        let mut bonds: [u128; 3] = [0; 3];
+        let mut claimed = Uint256::zero();
+        for _ in 0..300 {
+            env.block.time = env.block.time.plus_seconds(next(15));
+            let i = next(3) as usize;
+            match next(3) {
+                0 => {
+                    let amount = next(10000) as u128 + 1;
+                    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                        sender: stakers[i].to_string(),
+                        amount: Uint128::from(amount),
+                        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+                    });
+                    execute(deps.as_mut(), env.clone(), lp_info.clone(), msg).unwrap();
+                    bonds[i] += amount;
+                }
+                1 if bonds[i] > 0 => {
+                    let amount = next(bonds[i] as u64) as u128 + 1;
+                    let msg = ExecuteMsg::Unbond {
+                        amount: Some(Uint256::from(amount)),
+                        staker: None,
+                        token_id: None,
+                    };
+                    execute(deps.as_mut(), env.clone(), mock_info(stakers[i], &[]), msg).unwrap();
+                    bonds[i] -= amount;
+                }
+                2 if bonds[i] > 0 => {
+                    let msg = ExecuteMsg::ClaimRewards {
+                        staker: None,
+                        token_id: None,
+                    };
+                    let res = execute(deps.as_mut(), env.clone(), mock_info(stakers[i], &[]), msg)
+                        .unwrap();
+                    claimed += transfer_amount(&res.messages[0]);
+                }
+                _ => {}
+            }
+            assert!(claimed <= schedule_total);
+        }
+
+        // everyone claims after the schedule ended
+        env.block.time = Timestamp::from_seconds(default_genesis_seconds + 3000);
+        for staker in ["addr0000", "addr0001", "addr0002", "addr0003"] {
+            let staker_info: StakerInfoResponse = from_binary(
+                &query(
+                    deps.as_ref(),
+                    env.clone(),
+                    QueryMsg::StakerInfo {
+                        staker: staker.to_string(),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            if !staker_info.pending_reward.is_zero() {
+                let msg = ExecuteMsg::ClaimRewards {
+                    staker: None,
+                    token_id: None,
+                };
+                let res = execute(deps.as_mut(), env.clone(), mock_info(staker, &[]), msg).unwrap();
+                claimed += transfer_amount(&res.messages[0]);
+            }
+        }
+
+        // the stakers carry their rounding dust, less than a unit is left behind
+        // by each of the 4 stakers and by the global index
+        assert!(claimed <= schedule_total);
+        assert!(schedule_total - claimed < Uint256::from(5u128));
+
+        let res: RewardDustResponse =
+            from_binary(&query(deps.as_ref(), env, QueryMsg::RewardDust {}).unwrap()).unwrap();
+        assert!(res.reward_remainder <= Decimal256::from_uint256(schedule_total - claimed));
+    }
+}
+
+fn transfer_amount(msg: &SubMsg) -> Uint256 {
+    match &msg.msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
+            match from_binary::<Cw20ExecuteMsg>(msg).unwrap() {
+                Cw20ExecuteMsg::Transfer { amount, .. } => amount.into(),
+                _ => panic!("unexpected cw20 msg"),
+            }
+        }
+        _ => panic!("unexpected msg"),
+    }
+}
+
+#[test]
+fn test_undistributed_reward() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // nobody bonded for 10 seconds
+    // 100,000 rewards undistributed
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(10);
+    assert_eq!(
+        from_binary::<UndistributedRewardResponse>(
+            &query(deps.as_ref(), env.clone(), QueryMsg::UndistributedReward {}).unwrap()
+        )
+        .unwrap(),
+        UndistributedRewardResponse {
+            amount: Uint256::from(100000u128),
+            policy: UndistributedPolicy::Reclaim,
+        }
+    );
+
+    // failed with unauthorized
+    let msg = ExecuteMsg::RecoverUndistributed {
+        recipient: "addr0000".to_string(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let msg = ExecuteMsg::RecoverUndistributed {
+        recipient: "addr0000".to_string(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "miaw0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(100000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    let msg = ExecuteMsg::RecoverUndistributed {
+        recipient: "addr0000".to_string(),
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::NothingToRecover {});
+
+    // carry forward the next 10 seconds to the first staker
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        undistributed_policy: Some(UndistributedPolicy::CarryForward),
+        ..UpdateConfigMsg::default()
+    });
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    env.block.time = env.block.time.plus_seconds(10);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    let lp_info = mock_info("miawlp0000", &[]);
+    execute(deps.as_mut(), env.clone(), lp_info, msg).unwrap();
+
+    let msg = ExecuteMsg::RecoverUndistributed {
+        recipient: "addr0000".to_string(),
+    };
+    let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::UndistributedNotReclaimable {});
+
+    // 10 more seconds: 100,000 carried + 100,000 distributed
+    env.block.time = env.block.time.plus_seconds(10);
+    assert_eq!(
+        from_binary::<StakerInfoResponse>(
+            &query(
+                deps.as_ref(),
+                env,
+                QueryMsg::StakerInfo {
+                    staker: "addr0000".to_string(),
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        StakerInfoResponse {
+            staker: "addr0000".to_string(),
+            reward_index: Decimal256::from_ratio(2000, 1),
+            pending_reward: Uint256::from(200000u128),
+            bond_amount: Uint256::from(100u128),
+            weight: Uint256::from(100u128),
+            pending_fee: Uint256::zero(),
+            rewarder_rewards: None,
+        }
+    );
+}
+
+#[test]
+fn test_sweep_tokens() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    let info = mock_info("miawlp0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_balances(&[
+        (
+            &"miawlp0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(150u128))],
+        ),
+        (
+            &"miaw0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1200000u128))],
+        ),
+        (
+            &"token0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(7u128))],
+        ),
+        (
+            &"uusd".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000u128))],
+        ),
+    ]);
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    // failed with unauthorized
+    let msg = ExecuteMsg::SweepTokens {
+        asset: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
+        recipient: "addr0000".to_string(),
+        amount: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // only the LP tokens above the bonded amount can be swept
+    let info = mock_info("owner0000", &[]);
+    let msg = ExecuteMsg::SweepTokens {
+        asset: AssetInfo::Token {
+            contract_addr: "miawlp0000".to_string(),
+        },
+        recipient: "addr0000".to_string(),
+        amount: Some(Uint256::from(51u128)),
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::SweepAmountExceedsSurplus {});
+
+    let msg = ExecuteMsg::SweepTokens {
+        asset: AssetInfo::Token {
+            contract_addr: "miawlp0000".to_string(),
+        },
+        recipient: "addr0000".to_string(),
+        amount: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "sweep_tokens"),
+            attr("recipient", "addr0000"),
+            attr("amount", "50"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "miawlp0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(50u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // 100,000 rewards distributed and 900,000 still scheduled are kept aside
+    let msg = ExecuteMsg::SweepTokens {
+        asset: AssetInfo::Token {
+            contract_addr: "miaw0000".to_string(),
+        },
+        recipient: "addr0000".to_string(),
+        amount: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "miaw0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(200000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // unrelated tokens can be swept entirely
+    let msg = ExecuteMsg::SweepTokens {
+        asset: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
+        recipient: "addr0000".to_string(),
+        amount: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "token0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(7u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    let msg = ExecuteMsg::SweepTokens {
+        asset: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        recipient: "addr0000".to_string(),
+        amount: Some(Uint256::from(400u128)),
+    };
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(400u128),
+            }],
+        }))]
+    );
+}
+
+#[test]
+fn test_reward_vesting() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let owner_info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        vesting: Some(VestingConfig {
+            duration: 100u64,
+            early_withdraw_penalty: Decimal256::zero(),
+        }),
+        ..UpdateConfigMsg::default()
+    });
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    // bond 100 tokens for each staker
+    let lp_info = mock_info("miawlp0000", &[]);
+    for staker in ["addr0000", "addr0001"] {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: staker.to_string(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+        });
+        execute(deps.as_mut(), mock_env(), lp_info.clone(), msg).unwrap();
+    }
+
+    // 100,000 rewards for 10 seconds, half of it vests for addr0000
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(10);
+    let info = mock_info("addr0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::ClaimRewards {
+            staker: None,
+            token_id: None,
+        },
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "withdraw"),
+            attr("owner", "addr0000"),
+            attr("amount", "50000"),
+            attr("fee_amount", "0"),
+            attr(
+                "vesting_end_time",
+                (default_genesis_seconds + 110).to_string()
+            ),
+        ]
+    );
+
+    // a claim of the same block is added to the same schedule
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "owner0000".to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {}).unwrap(),
+    });
+    execute(deps.as_mut(), env.clone(), mock_info("miaw0000", &[]), msg).unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::ClaimRewards {
+            staker: None,
+            token_id: None,
+        },
+    )
+    .unwrap();
+
+    // half of the schedule vested after 50 seconds
+    env.block.time = env.block.time.plus_seconds(50);
+    assert_eq!(
+        from_binary::<VestingResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::Vesting {
+                    staker: "addr0000".to_string(),
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        VestingResponse {
+            staker: "addr0000".to_string(),
+            schedules: vec![VestingScheduleResponse {
+                start_time: default_genesis_seconds + 10,
+                end_time: default_genesis_seconds + 110,
+                amount: Uint256::from(50500u128),
+                withdrawn_amount: Uint256::zero(),
+            }],
+            claimable_amount: Uint256::from(25250u128),
+            locked_amount: Uint256::from(25250u128),
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::WithdrawVested {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "miaw0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(25250u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::WithdrawVested {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NothingToWithdraw {});
+
+    // early withdraw is disabled until a penalty is set
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::WithdrawVestedEarly {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::EarlyWithdrawDisabled {});
+
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        vesting: Some(VestingConfig {
+            duration: 100u64,
+            early_withdraw_penalty: Decimal256::percent(150),
+        }),
+        ..UpdateConfigMsg::default()
+    });
+    let err = execute(deps.as_mut(), env.clone(), owner_info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidPenaltyRate {});
+
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        vesting: Some(VestingConfig {
+            duration: 100u64,
+            early_withdraw_penalty: Decimal256::percent(50),
+        }),
+        ..UpdateConfigMsg::default()
+    });
+    execute(deps.as_mut(), env.clone(), owner_info, msg).unwrap();
+
+    // half of the 25,250 locked rewards is forfeited
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::WithdrawVestedEarly {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "withdraw_vested_early"),
+            attr("owner", "addr0000"),
+            attr("amount", "12625"),
+            attr("penalty_amount", "12625"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "miaw0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(12625u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // the penalty is redistributed to the stakers
+    // 50,000 + 500 + 250,000 + 6,312 rewards for addr0001
+    assert_eq!(
+        from_binary::<StakerInfoResponse>(
+            &query(
+                deps.as_ref(),
+                env,
+                QueryMsg::StakerInfo {
+                    staker: "addr0001".to_string(),
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        StakerInfoResponse {
+            staker: "addr0001".to_string(),
+            reward_index: Decimal256::from_ratio(3068125, 1000),
+            pending_reward: Uint256::from(306812u128),
+            bond_amount: Uint256::from(100u128),
+            weight: Uint256::from(100u128),
+            pending_fee: Uint256::zero(),
+            rewarder_rewards: None,
+        }
+    );
+}
+
+#[test]
+fn test_boost() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        boost: Some(BoostConfig {
+            contract: Some("gov0000".to_string()),
+            source: BoostSource::Cw20,
+        }),
+        ..UpdateConfigMsg::default()
+    });
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_balances(&[(
+        &"gov0000".to_string(),
+        &[
+            (&"addr0000".to_string(), &Uint128::from(100u128)),
+            (&"addr0001".to_string(), &Uint128::zero()),
+        ],
+    )]);
+
+    // bond 100 tokens for each staker
+    // addr0000 holds the whole boost supply, addr0001 nothing
+    let lp_info = mock_info("miawlp0000", &[]);
+    for staker in ["addr0000", "addr0001"] {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: staker.to_string(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+        });
+        execute(deps.as_mut(), mock_env(), lp_info.clone(), msg).unwrap();
+    }
+
+    // 140,000 rewards for 14 seconds split by weights 100 and 40
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(14);
+    assert_eq!(
+        from_binary::<StakersInfoResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::StakersByAddress {
+                    addresses: vec!["addr0000".to_string(), "addr0001".to_string()],
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        StakersInfoResponse {
+            stakers: vec![
+                StakerInfoResponse {
+                    staker: "addr0000".to_string(),
+                    reward_index: Decimal256::from_ratio(1000, 1),
+                    bond_amount: Uint256::from(100u128),
+                    weight: Uint256::from(100u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                    pending_reward: Uint256::from(100000u128),
+                },
+                StakerInfoResponse {
+                    staker: "addr0001".to_string(),
+                    reward_index: Decimal256::from_ratio(1000, 1),
+                    bond_amount: Uint256::from(100u128),
+                    weight: Uint256::from(40u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                    pending_reward: Uint256::from(40000u128),
+                },
+            ],
+            total_count: Some(2),
+            next_cursor: None,
+        }
+    );
+
+    let msg = ExecuteMsg::Kick {
+        staker: "addr0001".to_string(),
+    };
+    let info = mock_info("addr0002", &[]);
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::BoostNotDecayed {});
+
+    // the boost moves to addr0001, anyone can kick addr0000
+    deps.querier.with_balances(&[(
+        &"gov0000".to_string(),
+        &[
+            (&"addr0000".to_string(), &Uint128::zero()),
+            (&"addr0001".to_string(), &Uint128::from(100u128)),
+        ],
+    )]);
+
+    let msg = ExecuteMsg::Kick {
+        staker: "addr0000".to_string(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "kick"),
+            attr("staker", "addr0000"),
+            attr("weight", "40"),
+        ]
+    );
+
+    // 80,000 rewards for 8 seconds split by weights 40 and 40
+    env.block.time = env.block.time.plus_seconds(8);
+    let info = mock_info("addr0001", &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::ClaimRewards {
+            staker: None,
+            token_id: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "miaw0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0001".to_string(),
+                amount: Uint128::from(80000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // addr0001 is re-checkpointed with the full boost
+    assert_eq!(
+        from_binary::<StakersInfoResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::StakersByAddress {
+                    addresses: vec!["addr0000".to_string(), "addr0001".to_string()],
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        StakersInfoResponse {
+            stakers: vec![
+                StakerInfoResponse {
+                    staker: "addr0000".to_string(),
+                    reward_index: Decimal256::from_ratio(2000, 1),
+                    bond_amount: Uint256::from(100u128),
+                    weight: Uint256::from(40u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                    pending_reward: Uint256::from(140000u128),
+                },
+                StakerInfoResponse {
+                    staker: "addr0001".to_string(),
+                    reward_index: Decimal256::from_ratio(2000, 1),
+                    bond_amount: Uint256::from(100u128),
+                    weight: Uint256::from(100u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                    pending_reward: Uint256::zero(),
+                },
+            ],
+            total_count: Some(2),
+            next_cursor: None,
+        }
+    );
+
+    // the boost can be read from a staking contract instead, where addr0000
+    // holds the whole stake and the boost of addr0001 decays
+    deps.querier.with_balances(&[(
+        &"staking0000".to_string(),
+        &[(&"addr0000".to_string(), &Uint128::from(100u128))],
+    )]);
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        boost: Some(BoostConfig {
+            contract: Some("staking0000".to_string()),
+            source: BoostSource::Staking,
+        }),
+        ..UpdateConfigMsg::default()
+    });
+    let owner_info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), env.clone(), owner_info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::Kick {
+        staker: "addr0001".to_string(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("addr0002", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "kick"),
+            attr("staker", "addr0001"),
+            attr("weight", "40"),
+        ]
+    );
+
+    // the boost contract can be removed
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        boost: Some(BoostConfig::default()),
+        ..UpdateConfigMsg::default()
+    });
+    execute(deps.as_mut(), env.clone(), owner_info, msg).unwrap();
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), env, QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        config.boost,
+        BoostConfig {
+            contract: None,
+            source: BoostSource::Cw20,
+        }
+    );
+}
+
+#[test]
+fn test_referral() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        reward_fees: Some(RewardFees {
+            referral_rate: Decimal256::percent(25),
+            ..RewardFees::default()
+        }),
+        ..UpdateConfigMsg::default()
+    });
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidReferralRate {});
+
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        reward_fees: Some(RewardFees {
+            referral_rate: Decimal256::percent(10),
+            ..RewardFees::default()
+        }),
+        ..UpdateConfigMsg::default()
+    });
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // addr0000 refers addr0001
+    let lp_info = mock_info("miawlp0000", &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            referrer: Some("addr0000".to_string()),
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), lp_info.clone(), msg).unwrap();
+
+    // the referrer is recorded once
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            referrer: Some("addr0002".to_string()),
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), lp_info.clone(), msg).unwrap();
+
+    // failed with self referral
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0002".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            referrer: Some("addr0002".to_string()),
+        })
+        .unwrap(),
+    });
+    let err = execute(deps.as_mut(), mock_env(), lp_info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::SelfReferral {});
+
+    // failed with referral cycle
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            referrer: Some("addr0001".to_string()),
+        })
+        .unwrap(),
+    });
+    let err = execute(deps.as_mut(), mock_env(), lp_info, msg).unwrap_err();
+    assert_eq!(err, ContractError::ReferralCycle {});
+
+    assert_eq!(
+        from_binary::<RefereesResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Referees {
+                    referrer: "addr0000".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        RefereesResponse {
+            referees: vec!["addr0001".to_string()],
+            next_cursor: None,
+        }
+    );
+    assert_eq!(
+        from_binary::<RefereesResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Referees {
+                    referrer: "addr0002".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        RefereesResponse {
+            referees: vec![],
+            next_cursor: None,
+        }
+    );
+
+    // 100,000 rewards for 10 seconds, the 10,000 of the referrer are capped
+    // by the referral reserve and paid on top of the claim
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    // failed when not funded with the reward token
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "owner0000".to_string(),
+        amount: Uint128::from(5000u128),
+        msg: to_binary(&Cw20HookMsg::DepositReferralReserve {}).unwrap(),
+    });
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("miawlp0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("miaw0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deposit_referral_reserve"),
+            attr("sender", "owner0000"),
+            attr("amount", "5000"),
+        ]
+    );
+
+    // the reserve cannot be swept
+    deps.querier.with_balances(&[(
+        &"miaw0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1005000u128))],
+    )]);
+    let msg = ExecuteMsg::SweepTokens {
+        asset: AssetInfo::Token {
+            contract_addr: "miaw0000".to_string(),
+        },
+        recipient: "owner0000".to_string(),
+        amount: Some(Uint256::from(1u128)),
+    };
+    let err = execute(deps.as_mut(), env.clone(), mock_info("owner0000", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::SweepAmountExceedsSurplus {});
+
+    // the simulation splits the claim the same way
+    let res: SimulationResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::SimulateClaim {
+                staker: "addr0001".to_string(),
+                time: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        (res.reward_amount, res.fee_amount, res.referral_amount),
+        (
+            Uint256::from(100000u128),
+            Uint256::from(0u128),
+            Uint256::from(5000u128),
+        )
+    );
+    assert_eq!(res.state.referral_reserve, Uint256::zero());
+
+    let info = mock_info("addr0001", &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::ClaimRewards {
+            staker: None,
+            token_id: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "withdraw"),
+            attr("owner", "addr0001"),
+            attr("amount", "100000"),
+            attr("fee_amount", "0"),
+            attr("referrer", "addr0000"),
+            attr("referral_amount", "5000"),
+        ]
+    );
+
+    assert_eq!(
+        from_binary::<ReferralEarningsResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::ReferralEarnings {
+                    referrer: "addr0000".to_string(),
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        ReferralEarningsResponse {
+            referrer: "addr0000".to_string(),
+            amount: Uint256::from(5000u128),
+        }
+    );
+    assert_eq!(
+        from_binary::<StakerInfoResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::StakerInfo {
+                    staker: "addr0000".to_string(),
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        StakerInfoResponse {
+            staker: "addr0000".to_string(),
+            reward_index: Decimal256::from_ratio(500, 1),
+            bond_amount: Uint256::zero(),
+            weight: Uint256::zero(),
+            pending_fee: Uint256::zero(),
+            rewarder_rewards: None,
+            pending_reward: Uint256::from(5000u128),
+        }
+    );
+
+    // the referrer claims the referral reward
+    let info = mock_info("addr0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::ClaimRewards {
+            staker: None,
+            token_id: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "miaw0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(5000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // the claims never exceed the emitted rewards and the referral reserve
+    let res = query(deps.as_ref(), env, QueryMsg::State {}).unwrap();
+    let state: StateResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        state.cumulative_reward_distributed,
+        Uint256::from(105000u128)
+    );
+    assert_eq!(state.cumulative_reward_claimed, Uint256::from(105000u128));
+    assert_eq!(state.referral_reserve, Uint256::zero());
+}
+
+#[test]
+fn test_reward_fee() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // failed with the fee rate above the maximum
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        reward_fees: Some(RewardFees {
+            reward_fee_rate: Decimal256::percent(25),
+            fee_collector: Some("collector0000".to_string()),
+            ..RewardFees::default()
+        }),
+        ..UpdateConfigMsg::default()
+    });
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidRewardFeeRate {});
+
+    // failed without fee collector
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        reward_fees: Some(RewardFees {
+            reward_fee_rate: Decimal256::percent(10),
+            ..RewardFees::default()
+        }),
+        ..UpdateConfigMsg::default()
+    });
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::FeeCollectorNotSet {});
+
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        reward_fees: Some(RewardFees {
+            reward_fee_rate: Decimal256::percent(10),
+            fee_collector: Some("collector0000".to_string()),
+            ..RewardFees::default()
+        }),
+        ..UpdateConfigMsg::default()
+    });
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    let info = mock_info("miawlp0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // 100,000 rewards for 10 seconds, 10,000 of it for the fee collector
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(10);
+    assert_eq!(
+        from_binary::<StakerInfoResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::StakerInfo {
+                    staker: "addr0000".to_string(),
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        StakerInfoResponse {
+            staker: "addr0000".to_string(),
+            reward_index: Decimal256::from_ratio(1000, 1),
+            bond_amount: Uint256::from(100u128),
+            weight: Uint256::from(100u128),
+            pending_reward: Uint256::from(100000u128),
+            pending_fee: Uint256::from(10000u128),
+            rewarder_rewards: None,
+        }
+    );
+    let res: SimulationResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::SimulateClaim {
+                staker: "addr0000".to_string(),
+                time: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        (res.reward_amount, res.fee_amount, res.referral_amount),
+        (
+            Uint256::from(90000u128),
+            Uint256::from(10000u128),
+            Uint256::from(0u128),
+        )
+    );
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        env,
+        info,
+        ExecuteMsg::ClaimRewards {
+            staker: None,
+            token_id: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "withdraw"),
+            attr("owner", "addr0000"),
+            attr("amount", "90000"),
+            attr("fee_amount", "10000"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "miaw0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(90000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "miaw0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "collector0000".to_string(),
+                    amount: Uint128::from(10000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+}
+
+#[test]
+fn test_bond_restrictions() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            mock_env().block.time.seconds(),
+            mock_env().block.time.seconds() + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // failed with the deposit fee rate above the maximum
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        bond_restrictions: Some(BondRestrictions {
+            allowlist_enabled: true,
+            max_bond_per_staker: Uint256::from(150u128),
+            max_total_bond: Uint256::from(200u128),
+            deposit_fee_rate: Decimal256::percent(20),
+            treasury: Some("treasury0000".to_string()),
+        }),
+        ..UpdateConfigMsg::default()
+    });
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidDepositFeeRate {});
+
+    // failed without treasury
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        bond_restrictions: Some(BondRestrictions {
+            allowlist_enabled: true,
+            max_bond_per_staker: Uint256::from(150u128),
+            max_total_bond: Uint256::from(200u128),
+            deposit_fee_rate: Decimal256::percent(10),
+            treasury: None,
+        }),
+        ..UpdateConfigMsg::default()
+    });
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::TreasuryNotSet {});
+
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        bond_restrictions: Some(BondRestrictions {
+            allowlist_enabled: true,
+            max_bond_per_staker: Uint256::from(150u128),
+            max_total_bond: Uint256::from(200u128),
+            deposit_fee_rate: Decimal256::percent(10),
+            treasury: Some("treasury0000".to_string()),
+        }),
+        ..UpdateConfigMsg::default()
+    });
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // failed with unauthorized
+    let msg = ExecuteMsg::UpdateAllowlist {
+        add: vec!["addr0000".to_string()],
+        remove: vec![],
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let msg = ExecuteMsg::UpdateAllowlist {
+        add: vec![
+            "addr0000".to_string(),
+            "addr0001".to_string(),
+            "addr0002".to_string(),
+        ],
+        remove: vec![],
+    };
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateAllowlist {
+        add: vec![],
+        remove: vec!["addr0002".to_string()],
+    };
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        from_binary::<AllowlistResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Allowlist {
+                    start_after: None,
+                    limit: Some(1u32),
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        AllowlistResponse {
+            addresses: vec!["addr0000".to_string()],
+            next_cursor: Some("addr0000".to_string()),
+        }
+    );
+    assert_eq!(
+        from_binary::<AllowlistResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Allowlist {
+                    start_after: Some("addr0000".to_string()),
+                    limit: None,
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        AllowlistResponse {
+            addresses: vec!["addr0001".to_string()],
+            next_cursor: None,
+        }
+    );
+
+    // failed with not allowlisted
+    let info = mock_info("miawlp0000", &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0002".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::NotAllowlisted {});
+
+    // the simulation applies the same restrictions
+    let simulate_bond = |deps: Deps, staker: &str, amount: u128, time: Option<u64>| {
+        query(
+            deps,
+            mock_env(),
+            QueryMsg::SimulateBond {
+                staker: staker.to_string(),
+                amount: Uint256::from(amount),
+                time,
+            },
+        )
+        .and_then(|res| Ok(from_binary::<SimulationResponse>(&res)?))
+    };
+    let err = simulate_bond(deps.as_ref(), "addr0002", 100, None).unwrap_err();
+    assert_eq!(err, ContractError::NotAllowlisted {});
+    let err = simulate_bond(deps.as_ref(), "addr0000", 200, None).unwrap_err();
+    assert_eq!(err, ContractError::StakerBondCapExceeded {});
+    let err = simulate_bond(
+        deps.as_ref(),
+        "addr0000",
+        100,
+        Some(mock_env().block.time.seconds() + 100),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::FarmNotActive {});
+
+    let res = simulate_bond(deps.as_ref(), "addr0000", 100, None).unwrap();
+    assert_eq!(res.staker_info.bond_amount, Uint256::from(90u128));
+    assert_eq!(res.fee_amount, Uint256::from(10u128));
+
+    // 10 of 100 tokens go to the treasury
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "bond"),
+            attr("owner", "addr0000"),
+            attr("amount", "90"),
+            attr("deposit_fee", "10"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "miawlp0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "treasury0000".to_string(),
+                amount: Uint128::from(10u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // 90 + 90 exceeds the cap per staker
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::StakerBondCapExceeded {});
+
+    // 90 + 135 exceeds the total cap
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(150u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::TotalBondCapExceeded {});
+}
+
+#[test]
+fn test_farm_status() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![
+            ScheduleEntry::linear(
+                default_genesis_seconds + 10,
+                default_genesis_seconds + 110,
+                Uint256::from(1000000u128),
+            ),
+            ScheduleEntry::linear(
+                default_genesis_seconds + 110,
+                default_genesis_seconds + 210,
+                Uint256::from(1000000u128),
+            ),
+        ],
+    };
+    let owner_info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    // the window is derived from the distribution schedule
+    assert_eq!(
+        from_binary::<StatusResponse>(
+            &query(deps.as_ref(), mock_env(), QueryMsg::Status {}).unwrap()
+        )
+        .unwrap(),
+        StatusResponse {
+            status: FarmStatus::Upcoming,
+            start_time: Some(default_genesis_seconds + 10),
+            end_time: Some(default_genesis_seconds + 210),
+        }
+    );
+
+    // failed before the farm starts
+    let lp_info = mock_info("miawlp0000", &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    let err = execute(deps.as_mut(), mock_env(), lp_info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::FarmNotActive {});
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(10);
+    execute(deps.as_mut(), env.clone(), lp_info.clone(), msg.clone()).unwrap();
+
+    // failed while paused
+    let update_msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        paused: Some(true),
+        ..UpdateConfigMsg::default()
+    });
+    execute(deps.as_mut(), env.clone(), owner_info.clone(), update_msg).unwrap();
+    assert_eq!(
+        from_binary::<StatusResponse>(
+            &query(deps.as_ref(), env.clone(), QueryMsg::Status {}).unwrap()
+        )
+        .unwrap()
+        .status,
+        FarmStatus::Paused
+    );
+    let err = execute(deps.as_mut(), env.clone(), lp_info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::FarmNotActive {});
+
+    // explicit window ends the farm earlier
+    let update_msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        farm_window: Some(FarmWindow {
+            start_time: Some(default_genesis_seconds + 100),
+            end_time: Some(default_genesis_seconds + 100),
+        }),
+        paused: Some(false),
+        ..UpdateConfigMsg::default()
+    });
+    let err = execute(deps.as_mut(), env.clone(), owner_info.clone(), update_msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidFarmWindow {});
+
+    let update_msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        farm_window: Some(FarmWindow {
+            start_time: None,
+            end_time: Some(default_genesis_seconds + 100),
+        }),
+        paused: Some(false),
+        ..UpdateConfigMsg::default()
+    });
+    execute(deps.as_mut(), env.clone(), owner_info, update_msg).unwrap();
+
+    env.block.time = env.block.time.plus_seconds(90);
+    assert_eq!(
+        from_binary::<StatusResponse>(
+            &query(deps.as_ref(), env.clone(), QueryMsg::Status {}).unwrap()
+        )
+        .unwrap(),
+        StatusResponse {
+            status: FarmStatus::Ended,
+            start_time: Some(default_genesis_seconds + 10),
+            end_time: Some(default_genesis_seconds + 100),
+        }
+    );
+    let err = execute(deps.as_mut(), env.clone(), lp_info, msg).unwrap_err();
+    assert_eq!(err, ContractError::FarmNotActive {});
+
+    // unbonding is still open
+    let msg = ExecuteMsg::Unbond {
+        amount: None,
+        staker: None,
+        token_id: None,
+    };
+    execute(deps.as_mut(), env, mock_info("addr0000", &[]), msg).unwrap();
+}
+
+#[test]
+fn test_transfer_position() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    let info = mock_info("miawlp0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(10);
+    let info = mock_info("addr0000", &[]);
+
+    // failed with self transfer
+    let msg = ExecuteMsg::TransferPosition {
+        recipient: "addr0000".to_string(),
+        amount: None,
+        include_rewards: None,
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidTransferRecipient {});
+
+    // failed with more than bond amount
+    let msg = ExecuteMsg::TransferPosition {
+        recipient: "addr0001".to_string(),
+        amount: Some(Uint256::from(200u128)),
+        include_rewards: None,
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidTransferAmount {});
+
+    // move 40 tokens, the rewards stay with addr0000
+    let msg = ExecuteMsg::TransferPosition {
+        recipient: "addr0001".to_string(),
+        amount: Some(Uint256::from(40u128)),
+        include_rewards: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "transfer_position"),
+            attr("from", "addr0000"),
+            attr("to", "addr0001"),
+            attr("amount", "40"),
+            attr("reward_amount", "0"),
+        ]
+    );
+    assert!(res.messages.is_empty());
+
+    assert_eq!(
+        from_binary::<StakersInfoResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::StakersByAddress {
+                    addresses: vec!["addr0000".to_string(), "addr0001".to_string()],
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        StakersInfoResponse {
+            stakers: vec![
+                StakerInfoResponse {
+                    staker: "addr0000".to_string(),
+                    reward_index: Decimal256::from_ratio(1000, 1),
+                    bond_amount: Uint256::from(60u128),
+                    weight: Uint256::from(60u128),
+                    pending_reward: Uint256::from(100000u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                },
+                StakerInfoResponse {
+                    staker: "addr0001".to_string(),
+                    reward_index: Decimal256::from_ratio(1000, 1),
+                    bond_amount: Uint256::from(40u128),
+                    weight: Uint256::from(40u128),
+                    pending_reward: Uint256::zero(),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                },
+            ],
+            total_count: Some(2),
+            next_cursor: None,
+        }
+    );
+
+    // move the whole position with the rewards
+    // 100,000 + 60,000 rewards of addr0000
+    env.block.time = env.block.time.plus_seconds(10);
+    let msg = ExecuteMsg::TransferPosition {
+        recipient: "addr0001".to_string(),
+        amount: None,
+        include_rewards: Some(true),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "transfer_position"),
+            attr("from", "addr0000"),
+            attr("to", "addr0001"),
+            attr("amount", "60"),
+            attr("reward_amount", "160000"),
+        ]
+    );
+
+    assert_eq!(
+        from_binary::<StakersInfoResponse>(
+            &query(
+                deps.as_ref(),
+                env,
+                QueryMsg::StakersInfo {
+                    start_after: None,
+                    limit: None,
+                    order_by: None,
+                    sort_by: None,
+                    min_bond: None,
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        StakersInfoResponse {
+            stakers: vec![StakerInfoResponse {
+                staker: "addr0001".to_string(),
+                reward_index: Decimal256::from_ratio(2000, 1),
+                bond_amount: Uint256::from(100u128),
+                weight: Uint256::from(100u128),
+                pending_reward: Uint256::from(200000u128),
+                pending_fee: Uint256::zero(),
+                rewarder_rewards: None,
+            }],
+            total_count: Some(1),
+            next_cursor: None,
+        }
+    );
+}
+
+#[test]
+fn test_positions() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens into a position, and 100 tokens as a staker
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::BondPosition {}).unwrap(),
+    });
+    let info = mock_info("miawlp0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "mint"),
+            attr("minter", MOCK_CONTRACT_ADDR),
+            attr("owner", "addr0000"),
+            attr("token_id", "1"),
+            attr("amount", "100"),
+            attr("deposit_fee", "0"),
+        ]
+    );
+    assert_eq!(res.events[1].ty, EVENT_BOND_POSITION);
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the position earns like a staker, 100,000 rewards split between both bonds
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(10);
+    assert_eq!(
+        from_binary::<AllNftInfoResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::AllNftInfo {
+                    token_id: "1".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        AllNftInfoResponse {
+            access: OwnerOfResponse {
+                owner: "addr0000".to_string(),
+                approvals: vec![],
+            },
+            info: NftInfoResponse {
+                token_uri: None,
+                extension: PositionResponse {
+                    bond_amount: Uint256::from(100u128),
+                    bond_time: default_genesis_seconds,
+                    reward_index: Decimal256::from_ratio(500, 1),
+                    pending_reward: Uint256::from(50000u128),
+                    weight: Uint256::from(100u128),
+                    pending_fee: Uint256::zero(),
+                },
+            },
+        }
+    );
+    assert_eq!(
+        from_binary::<StateResponse>(
+            &query(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap()
+        )
+        .unwrap()
+        .total_bond_amount,
+        Uint256::from(200u128)
+    );
+    assert_eq!(
+        from_binary::<NumTokensResponse>(
+            &query(deps.as_ref(), env.clone(), QueryMsg::NumTokens {}).unwrap()
+        )
+        .unwrap(),
+        NumTokensResponse { count: 1 }
+    );
+    assert_eq!(
+        from_binary::<ContractInfoResponse>(
+            &query(deps.as_ref(), env.clone(), QueryMsg::ContractInfo {}).unwrap()
+        )
+        .unwrap(),
+        ContractInfoResponse {
+            name: "MIAW LP Staking Position".to_string(),
+            symbol: "MIAW-LP-POS".to_string(),
+        }
+    );
+
+    // failed when not the owner nor an operator
+    let msg = ExecuteMsg::TransferNft {
+        recipient: "addr0001".to_string(),
+        token_id: "1".to_string(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // the hooks and the rewarder see the bond of the positions held by the stakers
+    let owner_info = mock_info("owner0000", &[]);
+    let add_hook_msg = ExecuteMsg::AddHook {
+        addr: "hook0000".to_string(),
+        revert_on_failure: None,
+        gas_limit: None,
+    };
+    execute(deps.as_mut(), env.clone(), owner_info.clone(), add_hook_msg).unwrap();
+    let set_rewarder_msg = ExecuteMsg::SetRewarder {
+        rewarder: Some("rewarder0000".to_string()),
+    };
+    execute(deps.as_mut(), env.clone(), owner_info, set_rewarder_msg).unwrap();
+    let rewarder_msg = |staker: &str, new_bond: u128, pending_miaw: u128| {
+        SubMsg::reply_on_error(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "rewarder0000".to_string(),
+                msg: to_binary(&RewarderExecuteMsg::OnMiawReward {
+                    staker: staker.to_string(),
+                    new_bond: Uint256::from(new_bond),
+                    pending_miaw: Uint256::from(pending_miaw),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            2,
+        )
+        .with_gas_limit(500000)
+    };
+    let hook_msg = |staker: &str, old_bond: u128, new_bond: u128| {
+        SubMsg::new(WasmMsg::Execute {
+            contract_addr: "hook0000".to_string(),
+            msg: to_binary(&StakeChangedExecuteMsg::StakeChangedHook(
+                StakeChangedHookMsg {
+                    addr: staker.to_string(),
+                    old_bond: Uint256::from(old_bond),
+                    new_bond: Uint256::from(new_bond),
+                },
+            ))
+            .unwrap(),
+            funds: vec![],
+        })
+    };
+
+    // the pending reward moves with the position
+    let res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "transfer_nft"),
+            attr("sender", "addr0000"),
+            attr("recipient", "addr0001"),
+            attr("token_id", "1"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![
+            rewarder_msg("addr0000", 100, 0),
+            rewarder_msg("addr0001", 100, 50000),
+            hook_msg("addr0000", 200, 100),
+            hook_msg("addr0001", 0, 100),
+        ]
+    );
+    for (owner, tokens) in [("addr0000", vec![]), ("addr0001", vec!["1".to_string()])] {
+        assert_eq!(
+            from_binary::<TokensResponse>(
+                &query(
+                    deps.as_ref(),
+                    env.clone(),
+                    QueryMsg::Tokens {
+                        owner: owner.to_string(),
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap()
+            )
+            .unwrap(),
+            TokensResponse { tokens }
+        );
+    }
+
+    // the previous owner cannot claim anymore
+    let msg = ExecuteMsg::ClaimRewards {
+        staker: None,
+        token_id: Some("1".to_string()),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("addr0001", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "withdraw"),
+            attr("owner", "addr0001"),
+            attr("token_id", "1"),
+            attr("amount", "50000"),
+            attr("fee_amount", "0"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "miaw0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0001".to_string(),
+                    amount: Uint128::from(50000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            rewarder_msg("addr0001", 100, 50000),
+        ]
+    );
+
+    // unbonding the whole position burns it
+    let msg = ExecuteMsg::Unbond {
+        amount: None,
+        staker: None,
+        token_id: Some("1".to_string()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("addr0001", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "miawlp0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0001".to_string(),
+                    amount: Uint128::from(100u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            rewarder_msg("addr0001", 0, 0),
+            hook_msg("addr0001", 100, 0),
+        ]
+    );
+    assert_eq!(
+        from_binary::<NumTokensResponse>(
+            &query(deps.as_ref(), env.clone(), QueryMsg::NumTokens {}).unwrap()
+        )
+        .unwrap(),
+        NumTokensResponse { count: 0 }
+    );
+    assert!(query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::OwnerOf {
+            token_id: "1".to_string(),
+            include_expired: None,
+        },
+    )
+    .is_err());
+
+    // token IDs are not reused, the receiving contract is notified
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::BondPosition {}).unwrap(),
+    });
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("miawlp0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            rewarder_msg("addr0000", 200, 0),
+            hook_msg("addr0000", 100, 200),
+        ]
+    );
+
+    let msg = ExecuteMsg::SendNft {
+        contract: "market0000".to_string(),
+        token_id: "2".to_string(),
+        msg: to_binary("list").unwrap(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages[..4],
+        vec![
+            rewarder_msg("addr0000", 100, 0),
+            rewarder_msg("market0000", 100, 0),
+            hook_msg("addr0000", 200, 100),
+            hook_msg("market0000", 0, 100),
+        ]
+    );
+    assert_eq!(
+        res.messages[4..],
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "market0000".to_string(),
+            msg: to_binary(&Cw721ReceiverExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+                sender: "addr0000".to_string(),
+                token_id: "2".to_string(),
+                msg: to_binary("list").unwrap(),
+            }))
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+    assert_eq!(
+        from_binary::<TokensResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::AllTokens {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        TokensResponse {
+            tokens: vec!["2".to_string()],
+        }
+    );
+
+    // the positions are distributed to after the stakers, 50,000 rewards for the position
+    env.block.time = env.block.time.plus_seconds(10);
+    let msg = ExecuteMsg::DistributeToStakers {
+        start_after: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), env, mock_info("owner0000", &[]), msg).unwrap();
+    assert_eq!(res.attributes[2], attr("position_count", "1"));
+    assert!(res.messages.contains(&SubMsg::new(WasmMsg::Execute {
+        contract_addr: "miaw0000".to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: "market0000".to_string(),
+            amount: Uint128::from(50000u128),
+        })
+        .unwrap(),
+        funds: vec![],
+    })));
+    assert!(res
+        .messages
+        .contains(&rewarder_msg("market0000", 100, 50000)));
+    assert_eq!(res.events.last().unwrap().ty, EVENT_CLAIM_POSITION_REWARDS);
+}
+
+#[test]
+fn test_position_approvals() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::BondPosition {}).unwrap(),
+    });
+    let info = mock_info("miawlp0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    // a staking operator can claim for the owner but not take the position
+    let msg = ExecuteMsg::ApproveOperator {
+        operator: "bot0000".to_string(),
+        expires: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::TransferNft {
+        recipient: "bot0000".to_string(),
+        token_id: "1".to_string(),
+    };
+    let err = execute(deps.as_mut(), env.clone(), mock_info("bot0000", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let msg = ExecuteMsg::SendNft {
+        contract: "bot0000".to_string(),
+        token_id: "1".to_string(),
+        msg: to_binary("take").unwrap(),
+    };
+    let err = execute(deps.as_mut(), env.clone(), mock_info("bot0000", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    assert_eq!(
+        from_binary::<OwnerOfResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::OwnerOf {
+                    token_id: "1".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        OwnerOfResponse {
+            owner: "addr0000".to_string(),
+            approvals: vec![],
+        }
+    );
+
+    let msg = ExecuteMsg::ClaimRewards {
+        staker: None,
+        token_id: Some("1".to_string()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("bot0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "miaw0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(100000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    // failed when approved by a non owner
+    let msg = ExecuteMsg::Approve {
+        spender: "market0000".to_string(),
+        token_id: "1".to_string(),
+        expires: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "approve"),
+            attr("sender", "addr0000"),
+            attr("spender", "market0000"),
+            attr("token_id", "1"),
+        ]
+    );
+    assert_eq!(
+        from_binary::<NftApprovalResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::Approval {
+                    token_id: "1".to_string(),
+                    spender: "market0000".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        NftApprovalResponse {
+            approval: NftApproval {
+                spender: "market0000".to_string(),
+                expires: Expiration::Never {},
+            },
+        }
+    );
+    let err = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Approval {
+            token_id: "1".to_string(),
+            spender: "bot0000".to_string(),
+            include_expired: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ApprovalNotFound {});
+
+    // the approved spender transfers the position, the approvals are cleared
+    let msg = ExecuteMsg::TransferNft {
+        recipient: "addr0001".to_string(),
+        token_id: "1".to_string(),
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("market0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        from_binary::<NftApprovalsResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::Approvals {
+                    token_id: "1".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        NftApprovalsResponse { approvals: vec![] }
+    );
+
+    // an operator approved for all the positions transfers them
+    let msg = ExecuteMsg::ApproveAll {
+        operator: "operator0000".to_string(),
+        expires: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("addr0001", &[]), msg).unwrap();
+    assert_eq!(
+        from_binary::<OperatorsResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::AllOperators {
+                    owner: "addr0001".to_string(),
+                    include_expired: None,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        OperatorsResponse {
+            operators: vec![NftApproval {
+                spender: "operator0000".to_string(),
+                expires: Expiration::Never {},
+            }],
+        }
+    );
+
+    let msg = ExecuteMsg::TransferNft {
+        recipient: "addr0002".to_string(),
+        token_id: "1".to_string(),
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("operator0000", &[]),
+        msg,
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::RevokeAll {
+        operator: "operator0000".to_string(),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("addr0001", &[]), msg).unwrap();
+    assert_eq!(
+        from_binary::<OperatorsResponse>(
+            &query(
+                deps.as_ref(),
+                env,
+                QueryMsg::AllOperators {
+                    owner: "addr0001".to_string(),
+                    include_expired: None,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        OperatorsResponse { operators: vec![] }
+    );
+}
+
+#[test]
+fn test_receipt_token() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // failed with unauthorized
+    let msg = ExecuteMsg::RegisterReceiptToken {
+        receipt_token: "receipt0000".to_string(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "register_receipt_token"),
+            attr("receipt_token", "receipt0000"),
+        ]
+    );
+
+    // failed with already registered
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::ReceiptTokenRegistrationClosed {});
+
+    // bond mints the receipt 1:1
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    let info = mock_info("miawlp0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "receipt0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(100u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    // positions only move with the receipts
+    let msg = ExecuteMsg::TransferPosition {
+        recipient: "addr0001".to_string(),
+        amount: None,
+        include_rewards: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::ReceiptTokenEnabled {});
+
+    // failed with unauthorized
+    let msg = ExecuteMsg::ReceiptTransferHook {
+        from: "addr0000".to_string(),
+        to: "addr0001".to_string(),
+        amount: Uint256::from(40u128),
+    };
+    let info = mock_info("addr0000", &[]);
+    let err = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // receipt transfer settles both and moves 40 of the bond
+    let info = mock_info("receipt0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "receipt_transfer"),
+            attr("from", "addr0000"),
+            attr("to", "addr0001"),
+            attr("amount", "40"),
+        ]
+    );
+
+    assert_eq!(
+        from_binary::<StakersInfoResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::StakersByAddress {
+                    addresses: vec!["addr0000".to_string(), "addr0001".to_string()],
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        StakersInfoResponse {
+            stakers: vec![
+                StakerInfoResponse {
+                    staker: "addr0000".to_string(),
+                    reward_index: Decimal256::from_ratio(1000, 1),
+                    bond_amount: Uint256::from(60u128),
+                    weight: Uint256::from(60u128),
+                    pending_reward: Uint256::from(100000u128),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                },
+                StakerInfoResponse {
+                    staker: "addr0001".to_string(),
+                    reward_index: Decimal256::from_ratio(1000, 1),
+                    bond_amount: Uint256::from(40u128),
+                    weight: Uint256::from(40u128),
+                    pending_reward: Uint256::zero(),
+                    pending_fee: Uint256::zero(),
+                    rewarder_rewards: None,
+                },
+            ],
+            total_count: Some(2),
+            next_cursor: None,
+        }
+    );
+
+    // unbond burns the receipt before returning the LP tokens
+    let msg = ExecuteMsg::Unbond {
+        amount: None,
+        staker: None,
+        token_id: None,
+    };
+    let info = mock_info("addr0001", &[]);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "receipt0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::BurnFrom {
+                    owner: "addr0001".to_string(),
+                    amount: Uint128::from(40u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "miawlp0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0001".to_string(),
+                    amount: Uint128::from(40u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+}
+
+#[test]
+fn test_operator_approvals() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    let info = mock_info("miawlp0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let mut env = mock_env();
+    let info = mock_info("addr0000", &[]);
+
+    // failed with self approval
+    let msg = ExecuteMsg::ApproveOperator {
+        operator: "addr0000".to_string(),
+        expires: None,
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::CannotApproveSelf {});
+
+    // failed with expired approval
+    let msg = ExecuteMsg::ApproveOperator {
+        operator: "operator0000".to_string(),
+        expires: Some(Expiration::AtHeight(env.block.height)),
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::ApprovalExpired {});
+
+    let expires = Expiration::AtHeight(env.block.height + 10);
+    let msg = ExecuteMsg::ApproveOperator {
+        operator: "operator0000".to_string(),
+        expires: Some(expires),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "approve_operator"),
+            attr("staker", "addr0000"),
+            attr("operator", "operator0000"),
+            attr("expires", expires.to_string()),
+        ]
+    );
+
+    assert_eq!(
+        from_binary::<ApprovalsResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::Operators {
+                    staker: "addr0000".to_string(),
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        ApprovalsResponse {
+            staker: "addr0000".to_string(),
+            approvals: vec![ApprovalResponse {
+                operator: "operator0000".to_string(),
+                expires,
+            }],
+        }
+    );
+
+    env.block.time = env.block.time.plus_seconds(10);
+
+    // failed with unapproved operator
+    let msg = ExecuteMsg::ClaimRewards {
+        staker: Some("addr0000".to_string()),
+        token_id: None,
+    };
+    let info = mock_info("operator0001", &[]);
+    let err = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // the operator claims, the rewards go to the staker
+    let info = mock_info("operator0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "miaw0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(100000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // the approval expires
+    env.block.height += 10;
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    assert_eq!(
+        from_binary::<ApprovalsResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::Operators {
+                    staker: "addr0000".to_string(),
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        ApprovalsResponse {
+            staker: "addr0000".to_string(),
+            approvals: vec![],
+        }
+    );
+
+    // approve without expiration
+    let msg = ExecuteMsg::ApproveOperator {
+        operator: "operator0000".to_string(),
+        expires: None,
+    };
+    let staker_info = mock_info("addr0000", &[]);
+    execute(deps.as_mut(), env.clone(), staker_info.clone(), msg).unwrap();
+
+    // the operator unbonds, the LP tokens go to the staker
+    let msg = ExecuteMsg::Unbond {
+        amount: Some(Uint256::from(40u128)),
+        staker: Some("addr0000".to_string()),
+        token_id: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "miawlp0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(40u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // failed after revoke
+    let revoke_msg = ExecuteMsg::RevokeOperator {
+        operator: "operator0000".to_string(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), staker_info, revoke_msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "revoke_operator"),
+            attr("staker", "addr0000"),
+            attr("operator", "operator0000"),
+        ]
+    );
+    let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn test_distribute_to_stakers() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens for each staker
+    for staker in ["addr0000", "addr0001", "addr0002"] {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: staker.to_string(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+        });
+        let info = mock_info("miawlp0000", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::BondPosition {}).unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("miawlp0000", &[]), msg).unwrap();
+
+    // 100,000 rewards for each staker and the position
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(40);
+    let info = mock_info("keeper0000", &[]);
+
+    // only the owner or the keeper can distribute
+    let msg = ExecuteMsg::DistributeToStakers {
+        start_after: None,
+        limit: Some(2),
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let set_keeper_msg = ExecuteMsg::SetKeeper {
+        keeper: Some("keeper0000".to_string()),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        set_keeper_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner0000", &[]),
+        set_keeper_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "set_keeper"), attr("keeper", "keeper0000")]
+    );
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    let mut messages: Vec<SubMsg> = res.messages.clone();
+    assert_eq!(
+        res.attributes[..5],
+        vec![
+            attr("action", "distribute_to_stakers"),
+            attr("staker_count", "2"),
+            attr("position_count", "0"),
+            attr("amount", "200000"),
+            attr("fee_amount", "0"),
+        ]
+    );
+    let next_cursor: String = res.attributes[5].value.clone();
+    assert_eq!(res.attributes[5].key, "next_cursor");
+    assert!(!next_cursor.is_empty());
+
+    let msg = ExecuteMsg::DistributeToStakers {
+        start_after: Some(next_cursor),
+        limit: Some(2),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    messages.extend(res.messages.clone());
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "distribute_to_stakers"),
+            attr("staker_count", "1"),
+            attr("position_count", "1"),
+            attr("amount", "200000"),
+            attr("fee_amount", "0"),
+            attr("next_cursor", "position:1"),
+        ]
+    );
+    assert_eq!(res.events[2].ty, EVENT_CLAIM_POSITION_REWARDS);
+
+    // the positions are done as well
+    let msg = ExecuteMsg::DistributeToStakers {
+        start_after: Some("position:1".to_string()),
+        limit: Some(2),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "distribute_to_stakers"),
+            attr("staker_count", "0"),
+            attr("position_count", "0"),
+            attr("amount", "0"),
+            attr("fee_amount", "0"),
+            attr("next_cursor", ""),
+        ]
+    );
+
+    // every staker and the position got paid once
+    messages.sort_by_key(|msg| format!("{:?}", msg));
+    assert_eq!(
+        messages,
+        ["addr0000", "addr0000", "addr0001", "addr0002"]
+            .iter()
+            .map(|staker| SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "miaw0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: staker.to_string(),
+                    amount: Uint128::from(100000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })))
+            .collect::<Vec<SubMsg>>()
+    );
+
+    // nothing left to distribute
+    let msg = ExecuteMsg::DistributeToStakers {
+        start_after: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(res.attributes[3], attr("amount", "0"));
+
+    let res = query(deps.as_ref(), env, QueryMsg::State {}).unwrap();
+    let state: StateResponse = from_binary(&res).unwrap();
+    assert_eq!(state.cumulative_reward_claimed, Uint256::from(400000u128));
+    assert_eq!(state.staker_count, 3);
+}
+
+#[test]
+fn test_events() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    let info = mock_info("miawlp0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.events,
+        vec![
+            Event::new(EVENT_DISTRIBUTE)
+                .add_attribute("reward_asset", "miaw0000")
+                .add_attribute("global_reward_index_before", "0")
+                .add_attribute("global_reward_index", "0")
+                .add_attribute("last_distributed", default_genesis_seconds.to_string())
+                .add_attribute("total_bond_amount", "100")
+                .add_attribute("total_weight", "100"),
+            Event::new(EVENT_BOND)
+                .add_attribute("staker", "addr0000")
+                .add_attribute("bond_amount", "100")
+                .add_attribute("reward_index", "0")
+                .add_attribute("pending_reward", "0")
+                .add_attribute("weight", "100")
+                .add_attribute("total_bond_amount", "100")
+                .add_attribute("amount", "100")
+                .add_attribute("deposit_fee", "0"),
+        ]
+    );
+
+    // 100,000 rewards distributed in 10 seconds
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(10);
+    let msg = ExecuteMsg::ClaimRewards {
+        staker: None,
+        token_id: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.events,
+        vec![
+            Event::new(EVENT_DISTRIBUTE)
+                .add_attribute("reward_asset", "miaw0000")
+                .add_attribute("global_reward_index_before", "0")
+                .add_attribute("global_reward_index", "1000")
+                .add_attribute(
+                    "last_distributed",
+                    (default_genesis_seconds + 10).to_string()
+                )
+                .add_attribute("total_bond_amount", "100")
+                .add_attribute("total_weight", "100"),
+            Event::new(EVENT_CLAIM_REWARDS)
+                .add_attribute("staker", "addr0000")
+                .add_attribute("bond_amount", "100")
+                .add_attribute("reward_index", "1000")
+                .add_attribute("pending_reward", "0")
+                .add_attribute("weight", "100")
+                .add_attribute("total_bond_amount", "100")
+                .add_attribute("amount", "100000")
+                .add_attribute("fee_amount", "0")
+                .add_attribute("sender", "addr0000"),
+        ]
+    );
+
+    // the owner settings emit their own events
+    let info = mock_info("owner0000", &[]);
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        paused: Some(true),
+        reward_deposit_duration: Some(100),
+        ..UpdateConfigMsg::default()
+    });
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.events[1],
+        Event::new(EVENT_UPDATE_CONFIG).add_attribute("updated", "paused,reward_deposit_duration")
+    );
+
+    let msg = ExecuteMsg::AddHook {
+        addr: "hook0000".to_string(),
+        revert_on_failure: Some(false),
+        gas_limit: Some(100000),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.events,
+        vec![Event::new(EVENT_ADD_HOOK)
+            .add_attribute("hook", "hook0000")
+            .add_attribute("revert_on_failure", "false")
+            .add_attribute("gas_limit", "100000")]
+    );
+
+    let msg = ExecuteMsg::ApproveOperator {
+        operator: "addr0001".to_string(),
+        expires: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.events,
+        vec![Event::new(EVENT_APPROVE_OPERATOR)
+            .add_attribute("staker", "addr0000")
+            .add_attribute("operator", "addr0001")
+            .add_attribute("expires", "expiration: never")]
+    );
+}
+
+#[test]
+fn test_stake_changed_hooks() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // failed with unauthorized
+    let msg = ExecuteMsg::AddHook {
+        addr: "hook0000".to_string(),
+        revert_on_failure: None,
+        gas_limit: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "add_hook"),
+            attr("hook", "hook0000"),
+            attr("revert_on_failure", "true"),
+            attr("gas_limit", ""),
+        ]
+    );
+
+    // failed with duplicated hook
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::HookAlreadyRegistered {});
+
+    let msg = ExecuteMsg::AddHook {
+        addr: "hook0001".to_string(),
+        revert_on_failure: Some(false),
+        gas_limit: None,
+    };
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let mut hooks: Vec<HookResponse> = from_binary::<HooksResponse>(
+        &query(deps.as_ref(), mock_env(), QueryMsg::Hooks {}).unwrap(),
+    )
+    .unwrap()
+    .hooks;
+    hooks.sort_by(|a, b| a.addr.cmp(&b.addr));
+    assert_eq!(
+        hooks,
+        vec![
+            HookResponse {
+                addr: "hook0000".to_string(),
+                revert_on_failure: true,
+                gas_limit: None,
+            },
+            HookResponse {
+                addr: "hook0001".to_string(),
+                revert_on_failure: false,
+                gas_limit: Some(300000),
+            },
+        ]
+    );
+
+    // bond notifies the hooks
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    let res = execute(deps.as_mut(), mock_env(), mock_info("miawlp0000", &[]), msg).unwrap();
+    let hook_msg = to_binary(&StakeChangedExecuteMsg::StakeChangedHook(
+        StakeChangedHookMsg {
+            addr: "addr0000".to_string(),
+            old_bond: Uint256::zero(),
+            new_bond: Uint256::from(100u128),
+        },
+    ))
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert!(res.messages.contains(&SubMsg::new(WasmMsg::Execute {
+        contract_addr: "hook0000".to_string(),
+        msg: hook_msg.clone(),
+        funds: vec![],
+    })));
+    assert!(res.messages.contains(
+        &SubMsg::reply_on_error(
+            WasmMsg::Execute {
+                contract_addr: "hook0001".to_string(),
+                msg: hook_msg,
+                funds: vec![],
+            },
+            1,
+        )
+        .with_gas_limit(300000)
+    ));
+
+    // the failure of hook0001 is swallowed
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 1,
+            result: ContractResult::Err("hook failed".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "stake_changed_hook_failed"),
+            attr("error", "hook failed"),
+        ]
+    );
+
+    // unbond notifies the remaining hook
+    let msg = ExecuteMsg::RemoveHook {
+        addr: "hook0000".to_string(),
+    };
+    execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::HookNotRegistered {});
+
+    let msg = ExecuteMsg::Unbond {
+        amount: Some(Uint256::from(40u128)),
+        staker: None,
+        token_id: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages[1],
+        SubMsg::reply_on_error(
+            WasmMsg::Execute {
+                contract_addr: "hook0001".to_string(),
+                msg: to_binary(&StakeChangedExecuteMsg::StakeChangedHook(
+                    StakeChangedHookMsg {
+                        addr: "addr0000".to_string(),
+                        old_bond: Uint256::from(100u128),
+                        new_bond: Uint256::from(60u128),
+                    },
+                ))
+                .unwrap(),
+                funds: vec![],
+            },
+            1,
+        )
+        .with_gas_limit(300000)
+    );
+
+    // failed with too many hooks
+    for i in 0..9 {
+        let msg = ExecuteMsg::AddHook {
+            addr: format!("hook{:04}", i + 2),
+            revert_on_failure: None,
+            gas_limit: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+    let msg = ExecuteMsg::AddHook {
+        addr: "hook0011".to_string(),
+        revert_on_failure: None,
+        gas_limit: None,
+    };
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::TooManyHooks { max: 10 });
+}
+
+#[test]
+fn test_rewarder() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // failed with unauthorized
+    let msg = ExecuteMsg::SetRewarder {
+        rewarder: Some("rewarder0000".to_string()),
+    };
+    let info = mock_info("addr0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "set_rewarder"),
+            attr("rewarder", "rewarder0000"),
+        ]
+    );
+
+    // bond notifies the rewarder
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    let res = execute(deps.as_mut(), mock_env(), mock_info("miawlp0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_error(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "rewarder0000".to_string(),
+                msg: to_binary(&RewarderExecuteMsg::OnMiawReward {
+                    staker: "addr0000".to_string(),
+                    new_bond: Uint256::from(100u128),
+                    pending_miaw: Uint256::zero(),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            2
+        )
+        .with_gas_limit(500000)]
+    );
+
+    // claim notifies the rewarder with the claimed rewards
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(10);
+    let msg = ExecuteMsg::ClaimRewards {
+        staker: None,
+        token_id: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "miaw0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(100000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::reply_on_error(
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "rewarder0000".to_string(),
+                    msg: to_binary(&RewarderExecuteMsg::OnMiawReward {
+                        staker: "addr0000".to_string(),
+                        new_bond: Uint256::from(100u128),
+                        pending_miaw: Uint256::from(100000u128),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+                2
+            )
+            .with_gas_limit(500000),
+        ]
+    );
+
+    // a failing rewarder does not revert the claim
+    let res = reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: 2,
+            result: ContractResult::Err("rewarder failed".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "rewarder_failed"),
+            attr("error", "rewarder failed"),
+        ]
+    );
+
+    // the staker info still succeeds when the rewarder query fails
+    let res: StakerInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::StakerInfo {
+                staker: "addr0000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.rewarder_rewards, None);
+
+    // the staker info includes the pending rewards of the rewarder
+    let rewards = vec![RewarderReward {
+        asset: AssetInfo::Token {
+            contract_addr: "reward0000".to_string(),
+        },
+        amount: Uint256::from(500u128),
+    }];
+    deps.querier
+        .with_rewarder_rewards(&[(&"addr0000".to_string(), rewards.clone())]);
+    let res: StakerInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::StakerInfo {
+                staker: "addr0000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.rewarder_rewards, Some(rewards));
+
+    // unset the rewarder
+    let msg = ExecuteMsg::SetRewarder { rewarder: None };
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let res: StakerInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env,
+            QueryMsg::StakerInfo {
+                staker: "addr0000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.rewarder_rewards, None);
+}
+
+#[test]
+fn test_deposit_reward() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("miawlp0000", &[]), msg).unwrap();
+
+    // failed with the LP token
+    let deposit_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "distributor0000".to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {}).unwrap(),
+    });
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("miawlp0000", &[]),
+        deposit_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // distributed at once
+    let miaw_info = mock_info("miaw0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        miaw_info.clone(),
+        deposit_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deposit_reward"),
+            attr("sender", "distributor0000"),
+            attr("amount", "1000"),
+        ]
+    );
+
+    let pending_reward = |deps: Deps, env: Env| -> Uint256 {
+        from_binary::<StakerInfoResponse>(
+            &query(
+                deps,
+                env,
+                QueryMsg::StakerInfo {
+                    staker: "addr0000".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+        .pending_reward
+    };
+    assert_eq!(
+        pending_reward(deps.as_ref(), mock_env()),
+        Uint256::from(1000u128)
+    );
+
+    // stream the deposits over 100 seconds
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        reward_deposit_duration: Some(100),
+        ..UpdateConfigMsg::default()
+    });
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        miaw_info.clone(),
+        deposit_msg.clone(),
+    )
+    .unwrap();
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(50);
+    assert_eq!(
+        pending_reward(deps.as_ref(), env.clone()),
+        Uint256::from(1500u128)
+    );
+
+    // the left 500 and the new deposit are streamed until the current end time
+    execute(deps.as_mut(), env.clone(), miaw_info, deposit_msg).unwrap();
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap();
+    let state: StateResponse = from_binary(&res).unwrap();
+    assert_eq!(state.reward_stream_amount, Uint256::from(1500u128));
+    assert_eq!(
+        state.reward_stream_end_time,
+        mock_env().block.time.seconds() + 100
+    );
+    assert_eq!(state.reward_rate, Decimal256::from_ratio(30, 1));
+
+    env.block.time = env.block.time.plus_seconds(25);
+    assert_eq!(
+        pending_reward(deps.as_ref(), env.clone()),
+        Uint256::from(2250u128)
+    );
+
+    // the owner's deposit spreads the left 750 and the amount over a fresh duration
+    let owner_deposit_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "owner0000".to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {}).unwrap(),
+    });
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("miaw0000", &[]),
+        owner_deposit_msg,
+    )
+    .unwrap();
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap();
+    let state: StateResponse = from_binary(&res).unwrap();
+    assert_eq!(state.reward_stream_amount, Uint256::from(1750u128));
+    assert_eq!(state.reward_stream_end_time, env.block.time.seconds() + 100);
+    assert_eq!(state.reward_rate, Decimal256::from_ratio(35, 2));
+
+    env.block.time = env.block.time.plus_seconds(50);
+    assert_eq!(
+        pending_reward(deps.as_ref(), env.clone()),
+        Uint256::from(3125u128)
+    );
+
+    env.block.time = env.block.time.plus_seconds(100);
+    assert_eq!(pending_reward(deps.as_ref(), env), Uint256::from(4000u128));
+}
+
+#[test]
+fn test_notify_reward_amount() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // stream the rewards over 100 seconds
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        reward_deposit_duration: Some(100),
+        ..UpdateConfigMsg::default()
+    });
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("miawlp0000", &[]), msg).unwrap();
+
+    deps.querier.with_balances(&[(
+        &"miaw0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(999u128))],
+    )]);
+
+    // failed with non-owner
+    let msg = ExecuteMsg::NotifyRewardAmount {
+        amount: Uint256::from(1000u128),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // failed with the rewards not sent yet
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::InsufficientRewardBalance {});
+
+    deps.querier.with_balances(&[(
+        &"miaw0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000u128))],
+    )]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "notify_reward_amount"),
+            attr("amount", "1000"),
+            attr("reward_rate", "10"),
+        ]
+    );
+
+    let pending_reward = |deps: Deps, env: Env| -> Uint256 {
+        from_binary::<StakerInfoResponse>(
+            &query(
+                deps,
+                env,
+                QueryMsg::StakerInfo {
+                    staker: "addr0000".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+        .pending_reward
+    };
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(30);
+    assert_eq!(
+        pending_reward(deps.as_ref(), env.clone()),
+        Uint256::from(300u128)
+    );
+
+    // the 300 distributed and the 700 left to stream are owed already
+    let msg = ExecuteMsg::NotifyRewardAmount {
+        amount: Uint256::from(301u128),
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::InsufficientRewardBalance {});
+
+    deps.querier.with_balances(&[(
+        &"miaw0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1301u128))],
+    )]);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap();
+    let state: StateResponse = from_binary(&res).unwrap();
+    assert_eq!(state.reward_stream_amount, Uint256::from(1001u128));
+    assert_eq!(state.reward_stream_end_time, env.block.time.seconds() + 100);
+    assert_eq!(state.reward_rate, Decimal256::from_ratio(1001, 100));
+
+    // the rate is rounded down while streaming
+    env.block.time = env.block.time.plus_seconds(50);
+    assert_eq!(
+        pending_reward(deps.as_ref(), env.clone()),
+        Uint256::from(800u128)
+    );
+
+    // and the dust is released at the end time
+    env.block.time = env.block.time.plus_seconds(50);
+    assert_eq!(pending_reward(deps.as_ref(), env), Uint256::from(1301u128));
+}
+
+#[test]
+fn test_emission_curves() {
+    let mut deps = mock_dependencies(&[]);
+
+    // the legacy schedule entries without a curve are linear
+    let msg: InstantiateMsg = from_slice(
+        br#"{"miaw_token":"miaw0000","miaw_lp_token":"miawlp0000","distribution_schedule":[[100,200,"1000"]]}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        msg.distribution_schedule,
+        vec![ScheduleEntry::linear(100, 200, Uint256::from(1000u128))]
+    );
+
+    let t0: u64 = mock_env().block.time.seconds();
+    let info = mock_info("owner0000", &[]);
+
+    // failed with a zero halving period
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry(
+            t0,
+            t0 + 400,
+            Uint256::from(1500u128),
+            EmissionCurve::Halving { period: 0 },
+        )],
+    };
+    let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidDistributionSchedule {});
+
+    // failed with curve points not covering the entry
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry(
+            t0,
+            t0 + 200,
+            Uint256::from(2500u128),
+            EmissionCurve::PiecewiseLinear {
+                points: vec![(0, Decimal256::zero()), (100, Decimal256::one())],
+            },
+        )],
+    };
+    let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidDistributionSchedule {});
+
+    // 800, 400, 200 and 100 emitted in each 100 seconds
+    // 1000, 200 and 40 emitted in each 100 seconds
+    // 10 times the area under 0 -> 2 -> 1 over 200 seconds
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![
+            ScheduleEntry(
+                t0,
+                t0 + 400,
+                Uint256::from(1500u128),
+                EmissionCurve::Halving { period: 100 },
+            ),
+            ScheduleEntry(
+                t0 + 1000,
+                t0 + 1300,
+                Uint256::from(1240u128),
+                EmissionCurve::SteppedDecay {
+                    step: 100,
+                    decay: Decimal256::percent(20),
+                },
+            ),
+            ScheduleEntry(
+                t0 + 2000,
+                t0 + 2200,
+                Uint256::from(2500u128),
+                EmissionCurve::PiecewiseLinear {
+                    points: vec![
+                        (0, Decimal256::zero()),
+                        (100, Decimal256::from_ratio(2, 1)),
+                        (200, Decimal256::one()),
+                    ],
+                },
+            ),
+        ],
+    };
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::EmissionCurve {
+            timestamps: vec![t0 + 150, t0 + 1150, t0 + 2050, t0 + 2150, t0 + 3000],
+        },
+    )
+    .unwrap();
+    let curve: EmissionCurveResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        curve,
+        EmissionCurveResponse {
+            points: vec![
+                EmissionPointResponse {
+                    time: t0 + 150,
+                    emission_rate: Decimal256::from_ratio(4, 1),
+                    emitted_amount: Uint256::from(1000u128),
+                },
+                EmissionPointResponse {
+                    time: t0 + 1150,
+                    emission_rate: Decimal256::from_ratio(2, 1),
+                    emitted_amount: Uint256::from(2600u128),
+                },
+                EmissionPointResponse {
+                    time: t0 + 2050,
+                    emission_rate: Decimal256::from_ratio(10, 1),
+                    emitted_amount: Uint256::from(2990u128),
+                },
+                EmissionPointResponse {
+                    time: t0 + 2150,
+                    emission_rate: Decimal256::from_ratio(15, 1),
+                    emitted_amount: Uint256::from(4615u128),
+                },
+                EmissionPointResponse {
+                    time: t0 + 3000,
+                    emission_rate: Decimal256::zero(),
+                    emitted_amount: Uint256::from(5240u128),
+                },
+            ],
+        }
+    );
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::EmissionCurve {
+            timestamps: vec![t0; 31],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::TooManySamples { max: 30 });
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("miawlp0000", &[]), msg).unwrap();
+
+    let pending_reward = |deps: Deps, env: Env| -> Uint256 {
+        from_binary::<StakerInfoResponse>(
+            &query(
+                deps,
+                env,
+                QueryMsg::StakerInfo {
+                    staker: "addr0000".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+        .pending_reward
+    };
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(150);
+    assert_eq!(
+        pending_reward(deps.as_ref(), env.clone()),
+        Uint256::from(1000u128)
+    );
+
+    // the emission is integrated from the last distribution
+    env.block.time = env.block.time.plus_seconds(1000);
+    let msg = ExecuteMsg::ClaimRewards {
+        staker: None,
+        token_id: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+
+    env.block.time = env.block.time.plus_seconds(1000);
+    assert_eq!(
+        pending_reward(deps.as_ref(), env.clone()),
+        Uint256::from(2015u128)
+    );
+
+    env.block.time = env.block.time.plus_seconds(1000);
+    assert_eq!(pending_reward(deps.as_ref(), env), Uint256::from(2640u128));
+}
+
+#[test]
+fn test_migrate_positions() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let lp_info = mock_info("miawlp0000", &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), lp_info.clone(), msg).unwrap();
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::BondPosition {}).unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), lp_info.clone(), msg).unwrap();
+
+    // 50,000 rewards owed to addr0000 and to the position after 10 seconds
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(10);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0002".to_string(),
+        amount: Uint128::from(200u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    execute(deps.as_mut(), env.clone(), lp_info, msg).unwrap();
+
+    // the reward counters were not tracked before the upgrade
+    let mut state: State = STATE.load(deps.as_ref().storage).unwrap();
+    state.cumulative_reward_distributed = Uint256::zero();
+    state.total_weight = Uint256::zero();
+    STATE.save(deps.as_mut().storage, &state).unwrap();
+
+    migrate(deps.as_mut(), env, MigrateMsg { owner: None }).unwrap();
+    let state: State = STATE.load(deps.as_ref().storage).unwrap();
+    assert_eq!(state.total_weight, Uint256::from(400u128));
+    assert_eq!(state.staker_count, 2);
+    assert_eq!(
+        state.cumulative_reward_distributed,
+        Uint256::from(100000u128)
+    );
+}
+
+#[test]
+fn test_migrate_reward_counters() {
+    let mut deps = mock_dependencies(&[]);
+    let default_genesis_seconds: u64 = mock_env().block.time.seconds();
+
+    let msg = InstantiateMsg {
+        miaw_token: "miaw0000".to_string(),
+        miaw_lp_token: "miawlp0000".to_string(),
+        distribution_schedule: vec![ScheduleEntry::linear(
+            default_genesis_seconds,
+            default_genesis_seconds + 100,
+            Uint256::from(1000000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let lp_info = mock_info("miawlp0000", &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), lp_info.clone(), msg).unwrap();
+
+    // 100,000 rewards owed to addr0000 after 10 seconds
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(10);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { referrer: None }).unwrap(),
+    });
+    execute(deps.as_mut(), env.clone(), lp_info, msg).unwrap();
+
+    // the reward counters were not tracked before the upgrade
+    let mut state: State = STATE.load(deps.as_ref().storage).unwrap();
+    state.cumulative_reward_distributed = Uint256::zero();
+    STATE.save(deps.as_mut().storage, &state).unwrap();
+
+    migrate(deps.as_mut(), env.clone(), MigrateMsg { owner: None }).unwrap();
+    let state: State = STATE.load(deps.as_ref().storage).unwrap();
+    assert_eq!(
+        state.cumulative_reward_distributed,
+        Uint256::from(100000u128)
+    );
+
+    // only the tokens on top of the owed and scheduled rewards can be swept
+    deps.querier.with_balances(&[(
+        &"miaw0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000005u128))],
+    )]);
+    let sweep_msg = |amount: u128| ExecuteMsg::SweepTokens {
+        asset: AssetInfo::Token {
+            contract_addr: "miaw0000".to_string(),
+        },
+        recipient: "owner0000".to_string(),
+        amount: Some(Uint256::from(amount)),
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), sweep_msg(6)).unwrap_err();
+    assert_eq!(err, ContractError::SweepAmountExceedsSurplus {});
+
+    let msg = ExecuteMsg::ClaimRewards {
+        staker: None,
+        token_id: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+    deps.querier.with_balances(&[(
+        &"miaw0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(900005u128))],
+    )]);
+    execute(deps.as_mut(), env, info, sweep_msg(5)).unwrap();
 }